@@ -1,31 +1,152 @@
 use std::collections::HashMap;
-use crate::types::Value;
+use crate::{stack::Stack, types::Value};
+
+/**
+ * The type names that can be used in the signature of a function implementation.
+ * Any matches every value.
+ */
+pub const TYPE_NAMES: [&str; 11] = ["Int", "Float", "String", "Bool", "List", "Map", "Block", "Sequence", "Generator", "Symbol", "Any"];
+
+/**
+ * An Implementation is one block of a function together with the types it expects and its contract.
+ * The types are written in stack order, so the last type is the type of the top of the stack.
+ * An implementation without types is the untyped fallback of the function.
+ */
+#[derive(Debug, Clone)]
+pub struct Implementation {
+    pub types: Vec<String>,
+    pub block: Value,
+    pub contract: Contract,
+}
+
+impl Implementation {
+    /**
+     * This function checks if the implementation can be called with the values on the stack.
+     */
+    fn matches(&self, stack: &Stack) -> bool {
+        self.types.iter().rev().enumerate().all(|(index, expected)| {
+            match stack.peek(index) {
+                Some(value) => expected == "Any" || expected == value.type_name(),
+                None => false,
+            }
+        })
+    }
+
+    /**
+     * This function returns how specific the implementation is, more specific implementations win.
+     * Implementations with more concrete types are more specific, after that the ones expecting more values.
+     */
+    fn specificity(&self) -> (usize, usize) {
+        let concrete = self.types.iter().filter(|t| *t != "Any").count();
+        (concrete, self.types.len())
+    }
+
+    fn signature(&self) -> String {
+        format!("[{}]", self.types.join(" "))
+    }
+}
+
+/**
+ * A Macro is a word that reads its arguments from the code after it, the same way if and map do.
+ * The arguments are pushed to the stack as blocks before the block of the macro is run.
+ */
+#[derive(Debug, Clone)]
+pub struct Macro {
+    pub arguments: usize,
+    pub block: Value,
+}
+
+/**
+ * A Contract holds the conditions checked when an implementation is called.
+ * requires is checked on the arguments before the implementation runs, and ensures on its results after it.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Contract {
+    pub requires: Option<Value>,
+    pub ensures: Option<Value>,
+}
 
 /**
  * The Functions struct is a wrapper around a HashMap of function names and their corresponding values.
  * It is used to store functions that can be called from within the program.
- * It holds a hashmap of the name and the block of the function.
+ * It holds a hashmap of the name and the implementations of the function,
+ * the implementation that is run is chosen by the types of the values on top of the stack.
  */
+#[derive(Clone)]
 pub struct Functions {
-    pub functions: HashMap<String, Value>,
+    pub functions: HashMap<String, Vec<Implementation>>,
+    pub macros: HashMap<String, Macro>,
+    check_contracts: bool,
 }
 
 impl Functions {
     pub fn new() -> Self {
         Functions {
             functions: HashMap::new(),
+            macros: HashMap::new(),
+            check_contracts: true,
         }
     }
 
-    pub fn set(&mut self, name: &str, value: Value) {
-        self.functions.insert(name.to_string(), value);
+    pub fn set_check_contracts(&mut self, check_contracts: bool) {
+        self.check_contracts = check_contracts;
+    }
+
+    pub fn check_contracts(&self) -> bool {
+        self.check_contracts
     }
 
-    pub fn get(&self, name: &str) -> Option<&Value> {
-        if self.functions.contains_key(name) {
-            Some(&self.functions[name])
-        } else {
-            None
+    /**
+     * This function adds an implementation to a function.
+     * If the function already has an implementation with the same types it is replaced, together with its contract.
+     * A macro with the same name is removed.
+     */
+    pub fn set(&mut self, name: &str, types: Vec<String>, value: Value, contract: Contract) {
+        self.macros.remove(name);
+        let implementations = self.functions.entry(name.to_string()).or_default();
+        implementations.retain(|implementation| implementation.types != types);
+        implementations.push(Implementation { types, block: value, contract });
+    }
+
+    /**
+     * This function defines a macro, replacing any function or macro with the same name.
+     */
+    pub fn set_macro(&mut self, name: &str, arguments: usize, block: Value) {
+        self.functions.remove(name);
+        self.macros.insert(name.to_string(), Macro { arguments, block });
+    }
+
+    pub fn get_macro(&self, name: &str) -> Option<&Macro> {
+        self.macros.get(name)
+    }
+
+    /**
+     * This function finds the implementation to run when the function is called.
+     * It returns None if there is no function with the name,
+     * and an error if no implementation matches the stack or if the best match is not unique.
+     */
+    pub fn resolve(&self, name: &str, stack: &Stack) -> Result<Option<Implementation>, String> {
+        let implementations = match self.functions.get(name) {
+            Some(implementations) => implementations,
+            None => return Ok(None),
+        };
+        let candidates = implementations.iter()
+            .filter(|implementation| implementation.matches(stack))
+            .collect::<Vec<_>>();
+        let best = match candidates.iter().map(|implementation| implementation.specificity()).max() {
+            Some(best) => best,
+            None => {
+                let available = implementations.iter().map(|i| i.signature()).collect::<Vec<_>>().join(" ");
+                return Err(format!("No implementation of {} matches the stack {}, available: {}", name, stack, available));
+            }
+        };
+        let most_specific = candidates.into_iter()
+            .filter(|implementation| implementation.specificity() == best)
+            .collect::<Vec<_>>();
+        if most_specific.len() > 1 {
+            let matching = most_specific.iter().map(|i| i.signature()).collect::<Vec<_>>().join(" and ");
+            return Err(format!("Ambiguous call to {}, {} match the stack {}", name, matching, stack));
         }
+        Ok(Some(most_specific[0].clone()))
     }
-}
\ No newline at end of file
+}
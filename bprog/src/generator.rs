@@ -0,0 +1,122 @@
+use std::{cell::RefCell, fmt::Debug, sync::{atomic::{AtomicUsize, Ordering}, mpsc::{self, Receiver, Sender}, Arc, Mutex}, thread};
+
+use crate::{functions::Functions, signal::Signal, stack::{native_stack_size, Stack}, types::Value, variables::Variables};
+
+// what a generator sends back when it is resumed: a yielded value, None when it is done, or the signal that stopped it
+type Reply = Result<Option<Value>, Signal>;
+
+/**
+ * The most generators that can exist at the same time, every one of them has a thread waiting for it.
+ */
+const MAX_GENERATORS: usize = 1000;
+
+/**
+ * The maximum depth of a generator, so its thread can have a much smaller native stack than the interpreter.
+ */
+const GENERATOR_MAX_DEPTH: usize = 1000;
+
+// the number of generators that exist right now, a generator is counted until its last copy is dropped
+static LIVE_GENERATORS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // on the thread of a generator this holds the channels used by yield
+    static CHANNELS: RefCell<Option<(Sender<Reply>, Receiver<()>)>> = const { RefCell::new(None) };
+}
+
+struct State {
+    resume: Sender<()>,
+    replies: Receiver<Reply>,
+    finished: bool,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        LIVE_GENERATORS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/**
+ * A Generator runs a block that can stop in the middle with yield, and go on from there when it is resumed.
+ * To be able to stop in the middle of a block, it runs on its own thread and waits there between the values.
+ * It starts with a copy of the variables and functions from when it was made, and its own stack,
+ * which can be nested at most GENERATOR_MAX_DEPTH deep.
+ *
+ * All copies of a generator share the same state, so resuming one copy resumes them all.
+ */
+#[derive(Clone)]
+pub struct Generator {
+    state: Arc<Mutex<State>>,
+}
+
+impl Generator {
+    /**
+     * This function makes a generator for a block.
+     * The block does not start running before the generator is resumed the first time.
+     * If there are already MAX_GENERATORS generators, it will return an error.
+     */
+    pub fn new(block: Value, stack: &Stack, variables: &Variables, functions: &Functions) -> Result<Generator, String> {
+        LIVE_GENERATORS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| (live < MAX_GENERATORS).then_some(live + 1))
+            .map_err(|_| format!("Invalid operation, there can be at most {} generators at the same time", MAX_GENERATORS))?;
+        let (resume, resumed) = mpsc::channel::<()>();
+        let (reply, replies) = mpsc::channel::<Reply>();
+        let state = State { resume, replies, finished: false };        // made before the thread, so the count goes down again if it can not start
+        let mut variables = variables.clone();
+        variables.push_scope(variables.capture());     // local bindings made by the generator stay in the generator
+        let mut functions = functions.clone();
+        let mut stack = stack.fresh();
+        stack.set_max_depth(stack.max_depth().min(GENERATOR_MAX_DEPTH));
+        thread::Builder::new()
+            .name("generator".to_string())
+            .stack_size(native_stack_size(stack.max_depth()))     // enough native stack for the depth limit of the generator
+            .spawn(move || {
+                if resumed.recv().is_err() {     // the generator was dropped before it was ever used
+                    return;
+                }
+                CHANNELS.with(|channels| *channels.borrow_mut() = Some((reply.clone(), resumed)));
+                let result = block.exec(&mut stack, &mut variables, &mut functions);
+                let _ = reply.send(result.map(|_| None));
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(Generator { state: Arc::new(Mutex::new(state)) })
+    }
+
+    /**
+     * This function runs the generator until it yields the next value.
+     * It returns None when the block has ended, also every time after that.
+     * If the block fails, the error is returned and the generator is finished.
+     */
+    pub fn resume(&self) -> Result<Option<Value>, Signal> {
+        let mut state = self.state.try_lock().map_err(|_| "Invalid operation, the generator is already running")?;
+        if state.finished {
+            return Ok(None);
+        }
+        let reply = match state.resume.send(()) {
+            Ok(()) => state.replies.recv().unwrap_or(Ok(None)),
+            Err(_) => Ok(None),
+        };
+        if !matches!(reply, Ok(Some(_))) {
+            state.finished = true;
+        }
+        reply
+    }
+}
+
+/**
+ * This function sends a value from the block of a generator to the one resuming it,
+ * and waits until the generator is resumed again.
+ * Outside of a generator, it will return an error.
+ */
+pub fn yield_value(value: Value) -> Result<(), Signal> {
+    CHANNELS.with(|channels| {
+        let channels = channels.borrow();
+        let (reply, resumed) = channels.as_ref().ok_or("yield outside of a generator")?;
+        reply.send(Ok(Some(value))).map_err(|_| "the generator is no longer used")?;
+        resumed.recv().map_err(|_| Signal::from("the generator is no longer used"))
+    })
+}
+
+impl Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Generator")
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, fs, io};
+use std::{env, fs, io, thread};
 use types::Value;
 
 
@@ -7,18 +7,62 @@ mod types;
 mod stack;
 mod variables;
 mod functions;
+mod signal;
+mod sequence;
+mod generator;
 
 fn main() -> Result<(), String> {
-    let args = env::args().collect::<Vec<_>>();
+    // collect the options, anything that is not an option SHOULD be a file name
+    let mut max_depth = stack::DEFAULT_MAX_DEPTH;
+    let mut strict = false;
+    let mut contracts = true;
+    let mut files = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-depth" => {
+                max_depth = args.next()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|&max_depth| max_depth > 0)
+                    .ok_or("--max-depth expects a positive integer")?;
+                if max_depth > stack::MAX_DEPTH_LIMIT {
+                    return Err(format!("--max-depth can be at most {}", stack::MAX_DEPTH_LIMIT));
+                }
+            },
+            "--strict" => strict = true,
+            "--no-contracts" => contracts = false,
+            "--seed" => {
+                let seed = args.next()
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .ok_or("--seed expects an integer")?;
+                operations::random::set_seed(seed);
+            },
+            _ => files.push(arg),
+        }
+    }
+
+    let interpreter = thread::Builder::new()
+        .name("interpreter".to_string())
+        .stack_size(stack::native_stack_size(max_depth))      // the interpreter runs on its own thread so deep recursion hits the depth limit first
+        .spawn(move || run(files, max_depth, strict, contracts))
+        .map_err(|e| e.to_string())?;
+    match interpreter.join() {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),     // keep the panic message and exit code of file mode
+    }
+}
 
+fn run(files: Vec<String>, max_depth: usize, strict: bool, contracts: bool) -> Result<(), String> {
     // initialize variables, functions, and stack
     let mut variables = variables::Variables::new();
+    variables.set_strict(strict);
     let mut functions = functions::Functions::new();
+    functions.set_check_contracts(contracts);
     let mut stack = stack::Stack::new();
+    stack.set_max_depth(max_depth);
 
-    // if there is an argument it SHOULD be a file name
-    if args.len() > 1 {
-        if let Ok(text) = fs::read_to_string(&args[1]) {
+    if files.len() == 1 {
+        if let Ok(text) = fs::read_to_string(&files[0]) {
             let replaced = text.replace("\n", " ");      // replace newlines with spaces for easier parsing
             let block = Value::Block(replaced.split_whitespace().map(|s| s.to_string()).collect());
             if let Err(e) = block.exec(&mut stack, &mut variables, &mut functions) {        // execute the block untill error
@@ -26,11 +70,11 @@ fn main() -> Result<(), String> {
             }
             if stack.len() == 1 {        // if the stack has only one value on it, print it
                 println!("{}", stack.pop().unwrap());
-            } else {                    // if the stack has more or less than one value on it, panic    
+            } else {                    // if the stack has more or less than one value on it, panic
                 panic!("Invalid stack size, on exit only one value should be on the stack, but {} are on the stack", stack);
             }
         }
-    } else if args.len() == 1 {
+    } else if files.is_empty() {
         loop {      // loop untill program is terminated
             let mut buf = String::new();
             io::stdin().read_line(&mut buf).unwrap();
@@ -44,4 +88,4 @@ fn main() -> Result<(), String> {
         panic!("Invalid number of arguments, expected 0 or 1");
     }
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
-/**
+/*!
  * This module contains functions that perform arithmetic operations on values.
- * It contains functions for addition, subtraction, multiplication, division, and integer division.
+ * It contains functions for addition, subtraction, multiplication, division, integer division and comparisons.
+ * Integer arithmetic that overflows is an error instead of wrapping around.
  */
 
 use crate::{types::Value, stack::Stack};
@@ -33,17 +34,43 @@ pub fn div(stack: &mut Stack) -> Result<(), String> {
     Ok(())
 }
 
-pub fn lt(stack: &mut Stack) -> Result<(), String> {
+/**
+ * This function compares two values with <, >, <= or >=.
+ * A comparison with NaN is false, like for floats. If the values can not be compared, like a number and a string,
+ * it will return an error.
+ */
+fn compare(stack: &mut Stack, word: &str, test: fn(std::cmp::Ordering) -> bool) -> Result<(), String> {
     let b = stack.pop()?;
     let a = stack.pop()?;
-    stack.push(Value::Boolean(a<b));
+    let result = match a.partial_cmp(&b) {
+        Some(ordering) => test(ordering),
+        None if matches!((&a, &b), (Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_))) => false,
+        None => return Err(format!("Invalid operation {}, can not compare {} and {}", word, a, b)),
+    };
+    stack.push(Value::Boolean(result));
     Ok(())
 }
 
+pub fn lt(stack: &mut Stack) -> Result<(), String> {
+    compare(stack, "<", |ordering| ordering.is_lt())
+}
+
 pub fn gt(stack: &mut Stack) -> Result<(), String> {
+    compare(stack, ">", |ordering| ordering.is_gt())
+}
+
+pub fn le(stack: &mut Stack) -> Result<(), String> {
+    compare(stack, "<=", |ordering| ordering.is_le())
+}
+
+pub fn ge(stack: &mut Stack) -> Result<(), String> {
+    compare(stack, ">=", |ordering| ordering.is_ge())
+}
+
+pub fn ne(stack: &mut Stack) -> Result<(), String> {
     let b = stack.pop()?;
     let a = stack.pop()?;
-    stack.push(Value::Boolean(a>b));
+    stack.push(Value::Boolean(a!=b));
     Ok(())
 }
 
@@ -0,0 +1,84 @@
+/*!
+ * This module contains the combinators dip, keep, bi, tri and cleave, which run blocks on the values on the stack.
+ * The shuffle words are methods of Stack, but running a block needs the variables and functions too, so these are here.
+ */
+
+use crate::{functions, signal::Signal, stack::Stack, types::Value, variables};
+
+/**
+ * This function pops a block for a combinator from the stack.
+ * If the value is not a block, it will return an error.
+ */
+fn pop_block(stack: &mut Stack, word: &str) -> Result<Value, String> {
+    match stack.pop()? {
+        block @ (Value::Block(_) | Value::Closure(..)) => Ok(block),
+        _ => Err(format!("Invalid operation, {} expects a block", word)),
+    }
+}
+
+/**
+ * This function runs every block on its own copy of the value below them.
+ * It is used by bi, tri and cleave.
+ */
+fn apply_all(blocks: Vec<Value>, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let value = stack.pop()?;
+    for block in blocks {
+        stack.push(value.clone());
+        block.exec(stack, variables, functions)?;
+    }
+    Ok(())
+}
+
+/**
+ * This function runs a block with the value below it taken away, and puts the value back after, x { q } dip -> q x.
+ */
+pub fn dip(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let block = pop_block(stack, "dip")?;
+    let value = stack.pop()?;
+    block.exec(stack, variables, functions)?;
+    stack.push(value);
+    Ok(())
+}
+
+/**
+ * This function runs a block on the value below it, and pushes the value again after, x { q } keep -> q(x) x.
+ */
+pub fn keep(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let block = pop_block(stack, "keep")?;
+    let value = stack.pop()?;
+    stack.push(value.clone());
+    block.exec(stack, variables, functions)?;
+    stack.push(value);
+    Ok(())
+}
+
+/**
+ * This function runs two blocks on the same value, x { p } { q } bi -> p(x) q(x).
+ */
+pub fn bi(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let second = pop_block(stack, "bi")?;
+    let first = pop_block(stack, "bi")?;
+    apply_all(vec![first, second], stack, variables, functions)
+}
+
+/**
+ * This function runs three blocks on the same value, x { p } { q } { r } tri -> p(x) q(x) r(x).
+ */
+pub fn tri(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let third = pop_block(stack, "tri")?;
+    let second = pop_block(stack, "tri")?;
+    let first = pop_block(stack, "tri")?;
+    apply_all(vec![first, second, third], stack, variables, functions)
+}
+
+/**
+ * This function runs every block in a list on the same value, x [ { p } { q } ... ] cleave -> p(x) q(x) ...
+ * If the list holds something that is not a block, it will return an error.
+ */
+pub fn cleave(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let blocks = match stack.pop()? {
+        Value::List(blocks) if blocks.iter().all(|block| matches!(block, Value::Block(_) | Value::Closure(..))) => blocks,
+        _ => return Err("Invalid operation, cleave expects a list of blocks".into()),
+    };
+    apply_all(blocks, stack, variables, functions)
+}
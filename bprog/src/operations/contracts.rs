@@ -0,0 +1,50 @@
+/*!
+ * This module contains functions for checking that a program does what it should.
+ * It contains assert and assertEq, and the checks of the contracts of functions.
+ */
+
+use crate::{functions, signal::Signal, stack::Stack, types::Value, variables};
+
+/**
+ * This function checks that the value on the stack is true.
+ * If it is false it will return an error, and if it is not a boolean it will return an error as well.
+ */
+pub fn assert(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Boolean(true) => Ok(()),
+        Value::Boolean(false) => Err("Assertion failed: expected true, got false".to_string()),
+        value => Err(format!("Invalid operation, assert expects a boolean, got {}", value)),
+    }
+}
+
+/**
+ * This function checks that the two values on the stack are equal, the expected value is on top.
+ * If they are not equal, it will return an error that shows both values.
+ */
+pub fn assert_eq(stack: &mut Stack) -> Result<(), String> {
+    let expected = stack.pop()?;
+    let actual = stack.pop()?;
+    if actual != expected {
+        return Err(format!("Assertion failed: expected {}, got {}", expected, actual));
+    }
+    Ok(())
+}
+
+/**
+ * This function checks a condition of the contract of a function.
+ * The condition runs on a stack with a copy of only the values it checks, the arguments or the results,
+ * in its own local scope, so it can not change anything. It has to push a boolean.
+ * If it pushes false, it will return an error that shows the values.
+ */
+pub fn check(name: &str, kind: &str, condition: &Value, mut copy: Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let values = copy.to_string();
+    variables.push_scope(variables::Scope::default());
+    let result = condition.exec(&mut copy, variables, functions);
+    variables.pop_scope();
+    result?;
+    match copy.pop()? {
+        Value::Boolean(true) => Ok(()),
+        Value::Boolean(false) => Err(format!("{} of {} failed on {}", kind, name, values).into()),
+        value => Err(format!("Invalid operation, the {} of {} has to push a boolean, got {}", kind.to_lowercase(), name, value).into()),
+    }
+}
@@ -0,0 +1,284 @@
+/*!
+ * This module contains functions that read and write CSV.
+ * Fields can be quoted with ", and a quote in a quoted field is written as "". A quoted field can have the
+ * delimiter and newlines in it. Both \n and \r\n end a row, and empty lines are skipped.
+ *
+ * The options are a map with the keys
+ * delimiter, the character between the fields, or tab or space, a comma by default,
+ * header, true if the first row has the names of the columns, false by default,
+ * infer, true if columns where every field is a number are turned into numbers, true by default.
+ */
+
+use std::collections::BTreeMap;
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * Options are the settings that can be changed with an options map.
+ */
+struct Options {
+    delimiter: char,
+    header: bool,
+    infer: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { delimiter: ',', header: false, infer: true }
+    }
+}
+
+/**
+ * This function reads the options from an options map.
+ * If a key is not an option or the value has the wrong type, it will return an error.
+ */
+fn read_options(map: BTreeMap<String, Value>, word: &str) -> Result<Options, String> {
+    let mut options = Options::default();
+    for (key, value) in map {
+        match (key.as_str(), value) {
+            ("delimiter", Value::String(delimiter)) if delimiter == "tab" => options.delimiter = '\t',      // a string literal can not hold only whitespace
+            ("delimiter", Value::String(delimiter)) if delimiter == "space" => options.delimiter = ' ',
+            ("delimiter", Value::String(delimiter)) if delimiter.chars().count() == 1 && delimiter != "\"" => {
+                options.delimiter = delimiter.chars().next().unwrap();
+            },
+            ("header", Value::Boolean(header)) => options.header = header,
+            ("infer", Value::Boolean(infer)) => options.infer = infer,
+            ("delimiter" | "header" | "infer", value) => return Err(format!("Invalid option, {} can not be {} in {}", key, value, word)),
+            _ => return Err(format!("Invalid option, {} has no option {}", word, key)),
+        }
+    }
+    Ok(options)
+}
+
+/**
+ * This function splits CSV text into rows of fields.
+ * If a quoted field is not closed or has text after the closing quote, it will return an error.
+ */
+fn read_rows(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut quoted = false;     // true while inside a quoted field
+    let mut was_quoted = false;     // true after the closing quote of a field
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                },
+                '"' => {
+                    quoted = false;
+                    was_quoted = true;
+                },
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                },
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            c if c == delimiter => {
+                row.push(std::mem::take(&mut field));
+                was_quoted = false;
+            },
+            '\r' if chars.peek() == Some(&'\n') => {},
+            '\n' => {
+                if !row.is_empty() || !field.is_empty() || was_quoted {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                was_quoted = false;
+                line += 1;
+            },
+            '"' if field.is_empty() && !was_quoted => quoted = true,
+            _ if was_quoted => return Err(format!("Invalid CSV on line {}, there is text after a closing quote", line)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("Invalid CSV on line {}, a quoted field is not closed", line));
+    }
+    if !row.is_empty() || !field.is_empty() || was_quoted {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/**
+ * This function turns the columns where every field that is not empty is a number into numbers.
+ * A column is integers if all of them are integers, and floats otherwise. Empty fields are left as empty strings.
+ * A column with a field like 007, +1 or an integer too big for an Int is left as strings, since the number would not be written back the same.
+ */
+fn infer_columns(rows: Vec<Vec<String>>) -> Vec<Vec<Value>> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let is_float = |field: &str| field.parse::<f64>().is_ok_and(f64::is_finite);     // not inf, NaN or 1e999
+    let keeps_text = |field: &str| {        // 007, +1 or an integer too big for an Int would not be written back the same as a number
+        let digits = field.strip_prefix('-').unwrap_or(field);
+        let too_big = digits.chars().all(|c| c.is_ascii_digit()) && field.parse::<i64>().is_err();
+        field.starts_with('+') || (digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit())) || too_big
+    };
+    let kinds = (0..columns).map(|column| {
+        let fields = rows.iter().filter_map(|row| row.get(column)).filter(|field| !field.is_empty()).collect::<Vec<_>>();
+        if fields.is_empty() || fields.iter().any(|field| keeps_text(field)) {
+            None
+        } else if fields.iter().all(|field| field.parse::<i64>().is_ok()) {
+            Some(true)
+        } else if fields.iter().all(|field| is_float(field)) {
+            Some(false)
+        } else {
+            None
+        }
+    }).collect::<Vec<Option<bool>>>();
+    rows.into_iter().map(|row| {
+        row.into_iter().enumerate().map(|(column, field)| match kinds[column] {
+            Some(true) if !field.is_empty() => Value::Int(field.parse().unwrap()),
+            Some(false) if !field.is_empty() => Value::Float(field.parse().unwrap()),
+            _ => Value::String(field),
+        }).collect()
+    }).collect()
+}
+
+/**
+ * This function reads CSV text with options into a list of rows, or a list of maps if there is a header.
+ * A row in a map is missing the columns it does not have, and a row with more fields than the header is an error.
+ */
+fn read_csv(text: &str, options: Options) -> Result<Value, String> {
+    let mut rows = read_rows(text, options.delimiter)?;
+    let header = if options.header && !rows.is_empty() { Some(rows.remove(0)) } else { None };
+    let rows = if options.infer {
+        infer_columns(rows)
+    } else {
+        rows.into_iter().map(|row| row.into_iter().map(Value::String).collect()).collect()
+    };
+    let Some(header) = header else {
+        return Ok(Value::List(rows.into_iter().map(Value::List).collect()));
+    };
+    let mut records = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if row.len() > header.len() {
+            return Err(format!("Invalid CSV, row {} has {} fields but the header has {}", i + 1, row.len(), header.len()));
+        }
+        records.push(Value::Map(header.iter().cloned().zip(row).collect()));
+    }
+    Ok(Value::List(records))
+}
+
+/**
+ * This function pops the CSV text and reads it with the default options, a comma between the fields and no header.
+ */
+pub fn parse_csv(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::String(text) => {
+            stack.push(read_csv(&text, Options::default())?);
+            Ok(())
+        },
+        _ => Err("Invalid operation, parseCsv expects a string".to_string()),
+    }
+}
+
+/**
+ * This function reads CSV text with an options map, text options parseCsvWith.
+ */
+pub fn parse_csv_with(stack: &mut Stack) -> Result<(), String> {
+    let options = stack.pop()?;
+    let text = stack.pop()?;
+    match (text, options) {
+        (Value::String(text), Value::Map(options)) => {
+            stack.push(read_csv(&text, read_options(options, "parseCsvWith")?)?);
+            Ok(())
+        },
+        _ => Err("Invalid operation, parseCsvWith expects a string and a map of options".to_string()),
+    }
+}
+
+/**
+ * This function writes one field, in quotes if it has the delimiter, a quote, a newline or whitespace at the ends.
+ * If the value is a list, map or block, it will return an error.
+ */
+fn write_field(value: &Value, delimiter: char) -> Result<String, String> {
+    let field = match value {
+        Value::String(string) | Value::Symbol(string) => string.clone(),
+        Value::Int(_) | Value::Float(_) | Value::Boolean(_) => value.to_string(),
+        _ => return Err(format!("Invalid operation, a {} can not be a CSV field", value.type_name())),
+    };
+    if field.contains([delimiter, '"', '\n', '\r']) || field.trim() != field {
+        Ok(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Ok(field)
+    }
+}
+
+/**
+ * This function writes rows as CSV, with a \n after every row.
+ * The rows can be lists, or maps, which get a header row with every key from the maps.
+ * The columns are in the order the keys are first found, and a map that is missing a key gets an empty field.
+ */
+fn write_csv(rows: &[Value], delimiter: char) -> Result<String, String> {
+    let mut lines = Vec::new();
+    let separator = delimiter.to_string();
+    if rows.iter().all(|row| matches!(row, Value::Map(_))) && !rows.is_empty() {
+        let mut header: Vec<&String> = Vec::new();
+        for row in rows {
+            if let Value::Map(map) = row {
+                header.extend(map.keys().filter(|key| !header.contains(key)).collect::<Vec<_>>());
+            }
+        }
+        let names = header.iter().map(|name| write_field(&Value::String(name.to_string()), delimiter)).collect::<Result<Vec<_>, _>>()?;
+        lines.push(names.join(&separator));
+        for row in rows {
+            if let Value::Map(map) = row {
+                let fields = header.iter()
+                    .map(|name| map.get(*name).map_or(Ok(String::new()), |value| write_field(value, delimiter)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                lines.push(fields.join(&separator));
+            }
+        }
+    } else {
+        for row in rows {
+            match row {
+                Value::List(fields) => {
+                    let fields = fields.iter().map(|value| write_field(value, delimiter)).collect::<Result<Vec<_>, _>>()?;
+                    lines.push(fields.join(&separator));
+                },
+                _ => return Err("Invalid operation, toCsv expects a list of lists or a list of maps".to_string()),
+            }
+        }
+    }
+    Ok(lines.into_iter().map(|line| line + "\n").collect())
+}
+
+/**
+ * This function writes a list of rows as CSV with a comma between the fields.
+ */
+pub fn to_csv(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::List(rows) => {
+            stack.push(Value::String(write_csv(&rows, ',')?));
+            Ok(())
+        },
+        _ => Err("Invalid operation, toCsv expects a list of rows".to_string()),
+    }
+}
+
+/**
+ * This function writes a list of rows as CSV with an options map, rows options toCsvWith.
+ * Only the delimiter is used, since a list of maps always gets a header and the values already have their types.
+ */
+pub fn to_csv_with(stack: &mut Stack) -> Result<(), String> {
+    let options = stack.pop()?;
+    let rows = stack.pop()?;
+    match (rows, options) {
+        (Value::List(rows), Value::Map(options)) => {
+            let options = read_options(options, "toCsvWith")?;
+            stack.push(Value::String(write_csv(&rows, options.delimiter)?));
+            Ok(())
+        },
+        _ => Err("Invalid operation, toCsvWith expects a list of rows and a map of options".to_string()),
+    }
+}
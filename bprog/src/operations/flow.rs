@@ -1,9 +1,13 @@
-/**
+/*!
  * This module contains functions that are used to execute or read code blocks.
  * It contains functions for reading strings, lists, and blocks.
  */
 
-use crate::{functions, stack, types::{convert, Value}, variables};
+use std::iter::Peekable;
+
+use crate::{functions, signal::Signal, stack, types::{convert, Value}, variables};
+
+use super::contracts;
 
 /**
  * This function reads a string from an iterator.
@@ -15,7 +19,7 @@ where
     I: Iterator<Item = &'a String>,
 {
     let mut input = String::new();
-    while let Some(token) = iter.next() {
+    for token in iter.by_ref() {
         if token == "\"" {
             break;
         }
@@ -42,6 +46,9 @@ where
             "\"" => {
                 input.push(read_string(iter));
             },
+            "f\"" => {
+                return Err("Invalid syntax, an interpolated string can not be used in a list literal".to_string());
+            },
             "[" => {
                 input.push(read_list(iter)?);
             },
@@ -69,7 +76,7 @@ where
 {
     let mut input = Vec::new();
     let mut depth = 1;           // depth is used to keep track of the number of curly brackets, it has alredy read the first one
-    while let Some(token) = iter.next() {
+    for token in iter.by_ref() {
         match token.as_str() {
             "{" => {
                 depth += 1;
@@ -91,27 +98,133 @@ where
     Ok(Value::Block(input))
 }
 
-
-pub fn if_block<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+/**
+ * This function reads the tokens of a string or list literal from an iterator, including the opening and closing tokens.
+ * Strings, lists and blocks inside a list are read as a whole, so their closing tokens do not end the list.
+ */
+fn read_literal_tokens<'a, I>(open: &str, iter: &mut I, tokens: &mut Vec<String>)
 where
     I: Iterator<Item = &'a String>,
 {
-    let val = stack.pop()?;
-    let block_true = match iter.next(){     // read the true block
-        Some(token) if token == "{" => read_block(iter)?,
-        Some(token) => Value::Block(vec!(token.to_string())),
-        None => Value::Block(Vec::new()),
+    tokens.push(open.to_string());
+    let close = match open {
+        "\"" | "f\"" => "\"",
+        "[" => "]",
+        _ => "}",
     };
+    while let Some(token) = iter.next() {
+        if token == close {
+            tokens.push(token.to_string());
+            return;
+        }
+        let nested = match open {
+            "[" => token == "\"" || token == "f\"" || token == "[" || token == "{",
+            "{" => token == "{",
+            _ => false,
+        };
+        if nested {
+            read_literal_tokens(token, iter, tokens);
+        } else {
+            tokens.push(token.to_string());
+        }
+    }
+}
+
+/**
+ * This function reads the argument that follows a word, like the blocks after if or map.
+ * It takes an iterator and returns a Value.
+ * If the next token opens a block the whole block is read.
+ * A string or list literal is read as a whole into a block that pushes it, otherwise the single token is used as a block.
+ * This is shared by all the words that read their arguments from the code after them, including user macros.
+ */
+pub fn read_argument<'a, I>(iter: &mut I) -> Result<Value, String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    match iter.next() {
+        Some(token) if token == "{" => read_block(iter),
+        Some(token) if token == "\"" || token == "f\"" || token == "[" => {
+            let mut tokens = Vec::new();
+            read_literal_tokens(token, iter, &mut tokens);
+            Ok(Value::Block(tokens))
+        },
+        Some(token) => Ok(Value::Block(vec!(token.to_string()))),
+        None => Err("Invalid syntax, expected a block or a word".to_string()),
+    }
+}
 
-    let block_false = match iter.next(){    // read the false block
-        Some(token) if token == "{" => read_block(iter)?,
-        Some(token) => Value::Block(vec!(token.to_string())),
-        None => Value::Block(Vec::new()),
+/**
+ * This function gets the block for a word that runs a block on the elements of a list, like map or filter.
+ * The block is the argument after the word, read in the same way as read_argument,
+ * or for the With variants of the words, like mapWith, the block on top of the stack.
+ * The plain words never look at the stack for their block, so a list of blocks or a block as the start value of foldl is just data.
+ */
+pub fn block_argument<'a, I>(iter: &mut I, stack: &mut stack::Stack, from_stack: bool) -> Result<Value, String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    if !from_stack {
+        return read_argument(iter);
+    }
+    match stack.pop()? {
+        block @ (Value::Block(_) | Value::Closure(..)) => Ok(block),
+        value => Err(format!("Invalid operation, expected a block on top of the stack, got {}", value)),
+    }
+}
+
+/**
+ * This function reads all the arguments inside the block that follows a word.
+ * It is used by cond and case, where the block holds pairs of arguments.
+ */
+fn read_arguments<'a, I>(iter: &mut I) -> Result<Vec<Value>, String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let tokens = match read_argument(iter)? {
+        Value::Block(tokens) => tokens,
+        _ => return Err("Invalid syntax, expected a block".to_string()),
     };
+    let mut tokens = tokens.iter();
+    let mut arguments = Vec::new();
+    while tokens.len() > 0 {
+        arguments.push(read_argument(&mut tokens)?);
+    }
+    Ok(arguments)
+}
+
+/**
+ * This function turns an argument read by read_argument back into the literal it was written as.
+ * It is used for the values in a case.
+ */
+fn literal(argument: &Value) -> Result<Value, String> {
+    match argument {
+        Value::Block(tokens) => {
+            let mut iter = tokens.iter();
+            match iter.next().map(|token| token.as_str()) {
+                Some("\"") => Ok(read_string(&mut iter)),
+                Some("[") => read_list(&mut iter),
+                Some(token) if tokens.len() == 1 && token.len() > 1 && token.starts_with('\'') => {
+                    Ok(Value::Symbol(token[1..].to_string()))      // a quoted name is the symbol, the same as what 'name pushes
+                },
+                Some(token) if tokens.len() == 1 => Ok(convert(token)),
+                _ => Ok(argument.clone()),
+            }
+        },
+        _ => Ok(argument.clone()),
+    }
+}
+
+pub fn if_block<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let val = stack.pop()?;
+    let block_true = read_argument(iter)?;      // read the true block
+    let block_false = read_argument(iter)?;     // read the false block
 
     let condition = match val {             // make sure the value is a boolean
         Value::Boolean(value) => value,
-        _ => return Err(format!("Invalid syntax not a boolean")),
+        _ => return Err("Invalid syntax not a boolean".into()),
     };
 
     // if the condition is true run the true block, otherwise run the false block
@@ -120,37 +233,116 @@ where
     Ok(())
 }
 
+/**
+ * This function runs the body of a loop once.
+ * It returns false if the loop should stop because of a break, and true if it should go on, also after a continue.
+ * Other signals are passed on.
+ */
+pub fn run_body(block: &Value, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<bool, Signal> {
+    match block.exec(stack, variables, functions) {
+        Ok(()) | Err(Signal::Continue) => Ok(true),
+        Err(Signal::Break) => Ok(false),
+        Err(signal) => Err(signal),
+    }
+}
+
+/**
+ * This function runs the body of the first condition that is true.
+ * It is written as cond { <condition> <body> <condition> <body> ... <default> }.
+ * The conditions are run in order on the current stack, and each has to push a boolean.
+ * If no condition is true the default body is run, if there is one.
+ */
+pub fn cond<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let arguments = read_arguments(iter)?;
+    let mut pairs = arguments.chunks_exact(2);
+    for pair in pairs.by_ref() {
+        pair[0].exec(stack, variables, functions)?;
+        match stack.pop()? {
+            Value::Boolean(true) => return pair[1].exec(stack, variables, functions),
+            Value::Boolean(false) => {},
+            _ => return Err("Invalid syntax, a condition in cond has to push a boolean".into()),
+        }
+    }
+    if let [default] = pairs.remainder() {
+        default.exec(stack, variables, functions)?;
+    }
+    Ok(())
+}
+
+/**
+ * This function runs the body belonging to the value on top of the stack.
+ * It is written as case { <value> <body> <value> <body> ... <default> }.
+ * The value is taken from the stack and compared with the values in order.
+ * If no value is equal the default body is run, if there is one.
+ */
+pub fn case<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let arguments = read_arguments(iter)?;
+    let value = stack.pop()?;
+    let mut pairs = arguments.chunks_exact(2);
+    for pair in pairs.by_ref() {
+        if literal(&pair[0])? == value {
+            return pair[1].exec(stack, variables, functions);
+        }
+    }
+    if let [default] = pairs.remainder() {
+        default.exec(stack, variables, functions)?;
+    }
+    Ok(())
+}
+
 /**
  * This function executes a block a specified number of times.
  * It takes a stack, a block, a variables object, and a functions object as arguments.
  * It reads the number of times to execute the block from the iterator.
+ * If the number is zero or negative the block is not executed.
  * If the value is not a list or block, it will return an error.
  */
-pub fn times<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+pub fn times<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
 where
     I: Iterator<Item = &'a String>,
 {
-    let mut block = Value::Block(Vec::new());
-    if let Some(token) = iter.next() {
-        if token == "{" {
-            block = read_block(iter)?;
-        } else {
-            block = Value::Block(vec!(token.to_string()));
+    let block = read_argument(iter)?;
+    let times = stack.pop()?;
+    let times = match times {
+        Value::Int(value) => value,
+        _ => return Err("Invalid syntax not an integer".into()),
+    };
+    for _ in 0..times {
+        if !run_body(&block, stack, variables, functions)? {
+            break;
         }
-        let times = stack.pop()?;
-        let mut times = match times {
-            Value::Int(value) => value,
-            _ => return Err(format!("Invalid syntax not an integer")),
-        };
-        loop {
-            block.exec(stack, variables, functions)?;
-            times -= 1;
-            if times == 0 {
-                break;
-            }
+    }
+    Ok(())
+}
+
+/**
+ * This function executes a block as long as the check block pushes the boolean keep_going.
+ * The check is run before every iteration, so if it fails the first time the block is not executed.
+ * It is shared by loop and until, which stop when the check is true, and while, which stops when it is false.
+ */
+fn conditional_loop<'a, I>(iter: &mut I, keep_going: bool, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let check = read_argument(iter)?;      // read the check block
+    let block = read_argument(iter)?;      // read the block to execute while the condition holds
+    loop {
+        check.exec(stack, variables, functions)?;    // execute the check block
+        match stack.pop()? {                         // check if the result is true or false
+            Value::Boolean(value) if value == keep_going => {
+                if !run_body(&block, stack, variables, functions)? {
+                    break;
+                }
+            },
+            Value::Boolean(_) => { break },
+            _ => return Err("Invalid syntax, the condition of a loop has to push a boolean".into()),
         }
-    } else {
-        return Err(format!("Invalid syntax"));
     }
     Ok(())
 }
@@ -161,62 +353,254 @@ where
  * It reads the condition from the iterator.
  * If the value is not a list or block, it will return an error.
  */
-pub fn r#loop<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+pub fn r#loop<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
 where
     I: Iterator<Item = &'a String>,
 {
-    let check: Value;
-    let block: Value;
-    if let Some(token) = iter.next() {  // read the check block
-        if token == "{" {
-            check = read_block(iter)?;
-        } else {
-            check = Value::Block(vec!(token.to_string()));
-        }
-    } else {
-        return Err(format!("Invalid syntax"));
+    conditional_loop(iter, false, stack, variables, functions)
+}
+
+/**
+ * This function executes a block as long as a condition is true.
+ * It reads the condition and the block from the iterator.
+ */
+pub fn r#while<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    conditional_loop(iter, true, stack, variables, functions)
+}
+
+/**
+ * This function executes a block for every index from start to end, both included.
+ * It is written as start end step for { <expression>* }, and pushes the index before every iteration.
+ * With start end step for -> name { <expression>* } the index is bound to a local name instead.
+ * If the range is empty, like 5 1 1 for, the block is not executed. A step of zero returns an error.
+ */
+pub fn r#for<'a, I>(iter: &mut Peekable<I>, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let name = match iter.next_if(|token| *token == "->") {
+        Some(_) => Some(iter.next().ok_or("Invalid syntax, -> expects a name after it")?.to_string()),
+        None => None,
+    };
+    let block = read_argument(iter)?;
+    let (start, end, step) = match (stack.pop()?, stack.pop()?, stack.pop()?) {
+        (Value::Int(step), Value::Int(end), Value::Int(start)) => (start, end, step),
+        _ => return Err("Invalid syntax, for expects three integers: start end step".into()),
+    };
+    if step == 0 {
+        return Err("Invalid syntax, the step of for can not be zero".into());
     }
-    if let Some(token) = iter.next() {  // read the block to execute if the condition is met
-        if token == "{" {
-            block = read_block(iter)?;
-        } else {
-            block = Value::Block(vec!(token.to_string()));
+    let mut index = start;
+    while (step > 0 && index <= end) || (step < 0 && index >= end) {
+        match &name {
+            Some(name) => variables.bind(name, Value::Int(index)),
+            None => stack.push(Value::Int(index)),
         }
-    } else {
-        return Err(format!("Invalid syntax"));
-    }
-    loop {
-        check.exec(stack, variables, functions)?;    // execute the check block
-        match stack.pop()? {                         // check if the result is true or false
-            Value::Boolean(true) => { break },       // if true break out of the loop
-            Value::Boolean(false) => {
-                block.exec(stack, variables, functions)?;    // if false execute the block
-            },
-            _ => return Err(format!("Invalid syntax")),
+        if !run_body(&block, stack, variables, functions)? {
+            break;
         }
+        index = match index.checked_add(step) {
+            Some(index) => index,
+            None => break,      // the next index does not fit, so it would be past the end anyway
+        };
     }
     Ok(())
 }
 
+/**
+ * This function reads a name and a value from the stack, the value being on top.
+ * If the name is not a symbol, it will return an error.
+ */
+fn pop_binding(stack: &mut stack::Stack) -> Result<(String, Value), String> {
+    let value = stack.pop()?;
+    let name = stack.pop()?;
+    match name {
+        Value::Symbol(name) => Ok((name, value)),
+        _ => Err("Invalid syntax not a symbol".to_string()),
+    }
+}
+
 /**
  * This function assigns a value to a variable.
  * It takes a stack, a variables object, and a functions object as arguments.
  * It reads the variable name from the stack.
- * If the value is not a symbol, it will return an error.
+ * If the value is not a symbol, the variable is a constant, or it is already defined in strict mode, it will return an error.
  */
 pub fn assign(stack: &mut stack::Stack, variables: &mut variables::Variables) -> Result<(), String> {
+    let (name, value) = pop_binding(stack)?;
+    variables.assign(&name, value)
+}
+
+/**
+ * This function changes the value of a variable that is already defined.
+ * It reads the variable name from the stack.
+ * If the variable is not defined or is a constant, it will return an error.
+ */
+pub fn reassign(stack: &mut stack::Stack, variables: &mut variables::Variables) -> Result<(), String> {
+    let (name, value) = pop_binding(stack)?;
+    variables.reassign(&name, value)
+}
+
+/**
+ * This function defines a constant.
+ * It reads the constant name from the stack.
+ * If the name is already defined, it will return an error.
+ */
+pub fn constant(stack: &mut stack::Stack, variables: &mut variables::Variables) -> Result<(), String> {
+    let (name, value) = pop_binding(stack)?;
+    variables.define_constant(&name, value)
+}
+
+/**
+ * This function binds the value on top of the stack to a local name.
+ * The name is read from the iterator, so it is never looked up.
+ * Instead of a name there can be a list pattern like [ x y rest... ] or a record pattern like { name age },
+ * then the value is taken apart and all the names in the pattern are bound.
+ * If there is no name after the arrow, or the value does not match the pattern, it will return an error.
+ */
+pub fn bind<'a, I>(iter: &mut I, stack: &mut stack::Stack, variables: &mut variables::Variables) -> Result<(), String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let name = iter.next().ok_or("Invalid syntax, -> expects a name after it")?;
+    let pattern = match name.as_str() {
+        "[" => read_list(iter)?,
+        "{" => read_block(iter)?,
+        _ => {
+            let value = stack.pop()?;
+            variables.bind(name, value);
+            return Ok(());
+        },
+    };
     let value = stack.pop()?;
-    let name = stack.pop()?;
-    match name {
-        Value::Symbol(name) => {
-            variables.set(&name, value);
+    let mut bindings = Vec::new();
+    destructure(&pattern, value, &mut bindings)?;
+    for (name, value) in bindings {        // nothing is bound unless the whole pattern matched
+        variables.bind(&name, value);
+    }
+    Ok(())
+}
+
+/**
+ * This function matches a value against a pattern, and collects the names to bind.
+ * In a list pattern a name matches any element and _ matches without binding,
+ * the last name can end with ... to get the rest of the list, and a nested list matches a nested list.
+ * A literal like a number, string, boolean or 'symbol has to be equal to the element.
+ * A block of names is a record pattern, it matches a map and binds every name to the value with that key.
+ */
+fn destructure(pattern: &Value, value: Value, bindings: &mut Vec<(String, Value)>) -> Result<(), String> {
+    match (pattern, value) {
+        (Value::Symbol(name), _) if name == "_" => {},
+        (Value::Symbol(name), value) if name.starts_with('\'') => {
+            // a quoted symbol inside a list literal keeps its quote, so both forms match
+            if value != Value::Symbol(name[1..].to_string()) && value != Value::Symbol(name.to_string()) {
+                return Err(format!("Pattern mismatch: expected {}, got {}", name, value));
+            }
+        },
+        (Value::Symbol(name), value) => bindings.push((name.to_string(), value)),
+        (Value::List(patterns), Value::List(mut values)) => {
+            let (patterns, rest) = match patterns.last() {
+                Some(Value::Symbol(name)) if name.ends_with("...") => (&patterns[..patterns.len() - 1], Some(name.trim_end_matches("..."))),
+                _ => (&patterns[..], None),
+            };
+            let fits = match rest {
+                Some(_) => values.len() >= patterns.len(),
+                None => values.len() == patterns.len(),
+            };
+            if !fits {
+                let expected = if rest.is_some() { "at least " } else { "" };
+                return Err(format!("Pattern mismatch: expected a list of {}{} elements, got {}", expected, patterns.len(), Value::List(values)));
+            }
+            let remaining = values.split_off(patterns.len());
+            for (pattern, value) in patterns.iter().zip(values) {
+                destructure(pattern, value, bindings)?;
+            }
+            if let Some(rest) = rest.filter(|rest| !rest.is_empty() && *rest != "_") {
+                bindings.push((rest.to_string(), Value::List(remaining)));
+            }
+        },
+        (Value::Block(names), Value::Map(mut map)) => {
+            for name in names {
+                let value = map.remove(name).ok_or(format!("Pattern mismatch: the map has no key {}", name))?;
+                bindings.push((name.to_string(), value));
+            }
+        },
+        (Value::List(_), value) => return Err(format!("Pattern mismatch: expected a list, got {}", value)),
+        (Value::Block(_), value) => return Err(format!("Pattern mismatch: expected a map, got {}", value)),
+        (literal, value) => {
+            if *literal != value {
+                return Err(format!("Pattern mismatch: expected {}, got {}", literal, value));
+            }
         },
-        _ => return Err(format!("Invalid syntax not a symbol")),
     }
     Ok(())
 }
 
-pub fn exec(stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
+/**
+ * This function turns the block on top of the stack into a closure.
+ * The closure keeps a copy of the local bindings as they are now, and runs with them every time it is executed.
+ * If the value is not a block, it will return an error.
+ */
+pub fn closure(stack: &mut stack::Stack, variables: &mut variables::Variables) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Block(block) => {
+            stack.push(Value::Closure(block, variables.capture()));
+        },
+        Value::Closure(block, scope) => {       // a closure keeps the bindings it already has
+            stack.push(Value::Closure(block, scope));
+        },
+        _ => return Err("Invalid operation, closure expects a block".to_string()),
+    }
+    Ok(())
+}
+
+/**
+ * This function calls a user function.
+ * The function runs in a new local scope, which is removed again when it returns, also on errors.
+ * A return stops here, while a break or continue that is not inside a loop in the function becomes an error,
+ * so it never stops a loop in the caller.
+ * If the implementation has a contract, the precondition is checked on its arguments, as many as its signature has types,
+ * and the postcondition on the values that took their place.
+ * The return stack has to be as high when the function ends as when it started.
+ */
+pub fn call_function(name: &str, implementation: &functions::Implementation, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let contract = if functions.check_contracts() { implementation.contract.clone() } else { functions::Contract::default() };
+    let arguments = implementation.types.len();
+    if let Some(condition) = &contract.requires {
+        contracts::check(name, "Precondition", condition, stack.copy_top(arguments)?, variables, functions)?;
+    }
+    let base = stack.len() - arguments;     // the arguments always are on the stack, the types were matched
+    let aux_len = stack.aux_len();
+    variables.push_scope(variables::Scope::default());
+    let result = implementation.block.exec(stack, variables, functions);
+    variables.pop_scope();
+    if !matches!(result, Ok(()) | Err(Signal::Return)) {
+        stack.truncate_aux(aux_len);        // values left on the return stack by a failed call are thrown away
+    }
+    match result {
+        Ok(()) | Err(Signal::Return) => {},
+        Err(signal @ (Signal::Break | Signal::Continue)) => return Err(Signal::Error(signal.to_string())),
+        Err(signal) => return Err(signal),
+    }
+    if stack.aux_len() > aux_len {
+        let left = stack.aux_len() - aux_len;
+        stack.truncate_aux(aux_len);
+        return Err(format!("Return stack is not balanced at the end of {}, {} values were left on it", name, left).into());
+    }
+    if stack.aux_len() < aux_len {
+        return Err(format!("Return stack is not balanced at the end of {}, {} values were taken from the caller", name, aux_len - stack.aux_len()).into());
+    }
+    if let Some(condition) = &contract.ensures {
+        let results = stack.len().saturating_sub(base);
+        contracts::check(name, "Postcondition", condition, stack.copy_top(results)?, variables, functions)?;
+    }
+    Ok(())
+}
+
+pub fn exec(stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
     let block = stack.pop()?;
     block.exec(stack, variables, functions)?;
     Ok(())
@@ -226,16 +610,148 @@ pub fn exec(stack: &mut stack::Stack, variables: &mut variables::Variables, func
  * This function creates a new function.
  * It takes a stack, a variables object, and a functions object as arguments.
  * It reads the function name from the stack.
+ * If there is a list of type names between the name and the block, the block is added as the implementation for those types.
+ * After fun it reads the contract of the implementation, requires { <condition> } and ensures { <condition> }, which can be left out.
+ * A contract needs a signature, since the types tell how many arguments the conditions check.
  * If the value is not a symbol, it will return an error.
  */
-pub fn new_function(stack: &mut stack::Stack, functions: &mut functions::Functions) -> Result<(), String> {
+pub fn new_function<'a, I>(iter: &mut Peekable<I>, stack: &mut stack::Stack, functions: &mut functions::Functions) -> Result<(), String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let mut contract = functions::Contract::default();
+    while let Some(kind) = iter.next_if(|token| *token == "requires" || *token == "ensures") {
+        let condition = read_argument(iter)?;
+        if kind == "requires" {
+            contract.requires = Some(condition);
+        } else {
+            contract.ensures = Some(condition);
+        }
+    }
     let block = stack.pop()?;
-    let name = stack.pop()?;
+    let mut name = stack.pop()?;
+    let mut types = Vec::new();
+    if let Value::List(list) = name {       // the function has a signature
+        for value in list {
+            match value {
+                Value::Symbol(type_name) if functions::TYPE_NAMES.contains(&type_name.as_str()) => types.push(type_name),
+                _ => return Err(format!("Invalid type {} in signature, expected one of {}", value, functions::TYPE_NAMES.join(" "))),
+            }
+        }
+        name = stack.pop()?;
+    } else if contract.requires.is_some() || contract.ensures.is_some() {
+        return Err("Invalid syntax, a function with a contract needs a signature like [ Int ], use [ Any ] for any value".to_string());
+    }
     match name {
         Value::Symbol(name) => {
-            functions.set(&name, block);
+            functions.set(&name, types, block, contract);
         },
-        _ => return Err(format!("Invalid syntax not a symbol")),
+        _ => return Err("Invalid syntax not a symbol".to_string()),
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/**
+ * This function creates a new macro.
+ * It takes a stack and a functions object as arguments.
+ * It reads the name, the number of arguments and the block of the macro from the stack.
+ * If the name is not a symbol or the number of arguments is not a positive integer, it will return an error.
+ */
+pub fn new_macro(stack: &mut stack::Stack, functions: &mut functions::Functions) -> Result<(), String> {
+    let block = stack.pop()?;
+    let arguments = match stack.pop()? {
+        Value::Int(arguments) if arguments >= 0 => arguments as usize,
+        _ => return Err("Invalid syntax, a macro needs the number of arguments it reads".to_string()),
+    };
+    match stack.pop()? {
+        Value::Symbol(name) => {
+            functions.set_macro(&name, arguments, block);
+        },
+        _ => return Err("Invalid syntax not a symbol".to_string()),
+    }
+    Ok(())
+}
+
+/**
+ * This function calls a macro.
+ * It reads the arguments of the macro from the iterator, in the same way if and map read their blocks,
+ * pushes them to the stack in the order they were written and then runs the block of the macro.
+ */
+pub fn call_macro<'a, I>(iter: &mut I, definition: &functions::Macro, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    for _ in 0..definition.arguments {
+        let argument = read_argument(iter)?;
+        stack.push(argument);
+    }
+    definition.block.exec(stack, variables, functions)
+}
+
+/**
+ * This function runs a block and handles the errors and thrown values coming out of it.
+ * It is written as try { <expression>* } catch { <expression>* } finally { <expression>* }, where catch or finally can be left out.
+ * If the try block fails, the stack, the return stack and the named stacks are put back the way they were when the try started,
+ * also the values the block took, then the error message or thrown value is pushed and the catch block is run.
+ * The finally block is always run last, also when the error is not caught or the block is left with break, continue or return.
+ */
+pub fn try_block<'a, I>(iter: &mut Peekable<I>, stack: &mut stack::Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let body = read_argument(iter)?;
+    let handler = match iter.next_if(|token| *token == "catch") {
+        Some(_) => Some(read_argument(iter)?),
+        None => None,
+    };
+    let cleanup = match iter.next_if(|token| *token == "finally") {
+        Some(_) => Some(read_argument(iter)?),
+        None => None,
+    };
+    if handler.is_none() && cleanup.is_none() {
+        return Err("Invalid syntax, try expects catch or finally after the block".into());
+    }
+
+    let before = handler.as_ref().map(|_| stack.snapshot());      // only needed if there is a catch to run
+    let mut result = body.exec(stack, variables, functions);
+    if let (Err(signal), Some(handler)) = (&result, &handler) {
+        let caught = match signal {
+            Signal::Error(message) => Some(Value::String(message.clone())),
+            Signal::Throw(value) => Some(value.clone()),
+            _ => None,      // break, continue and return are not errors and go past the catch
+        };
+        if let (Some(caught), Some(before)) = (caught, before) {
+            stack.restore(before);      // undo what the failed block did to the stack, the return stack and the named stacks
+            stack.push(caught);
+            result = handler.exec(stack, variables, functions);
+        }
+    }
+    if let Some(cleanup) = cleanup {
+        cleanup.exec(stack, variables, functions)?;
+    }
+    result
+}
+
+/**
+ * These functions leave the innermost loop, go to its next iteration, or leave the current function.
+ */
+pub fn r#break() -> Result<(), Signal> {
+    Err(Signal::Break)
+}
+
+pub fn r#continue() -> Result<(), Signal> {
+    Err(Signal::Continue)
+}
+
+pub fn r#return() -> Result<(), Signal> {
+    Err(Signal::Return)
+}
+
+/**
+ * This function throws the value on top of the stack.
+ * It can be caught by a surrounding try, if there is none it stops the program like an error.
+ */
+pub fn throw(stack: &mut stack::Stack) -> Result<(), Signal> {
+    let value = stack.pop()?;
+    Err(Signal::Throw(value))
+}
@@ -0,0 +1,287 @@
+/*!
+ * This module contains functions that turn values into text.
+ * It contains format, which fills the placeholders in a template, interpolated strings and toString.
+ *
+ * A placeholder is written as {} for the next value, {0} for a value by position or {name} for a value by name.
+ * After a colon it can have a specifier like {:>8}, {:08.2} or {name:*^10}, with an optional fill character and
+ * alignment (< left, > right, ^ center), a 0 to pad numbers with zeros, a width and a precision.
+ * {{ and }} are written as { and }.
+ */
+
+use crate::{stack::Stack, types::Value, variables};
+
+use super::flow::read_string;
+
+/**
+ * A Key tells which value a placeholder is filled with.
+ */
+enum Key {
+    Next,
+    Index(usize),
+    Name(String),
+}
+
+/**
+ * A Spec is the specifier of a placeholder, the part after the colon.
+ */
+#[derive(Default)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+/**
+ * A Piece is a part of a template, either text that is written as it is or a placeholder.
+ */
+enum Piece {
+    Text(String),
+    Field(Key, Spec),
+}
+
+/**
+ * The biggest width or precision a specifier can have, so one template can not fill the memory.
+ */
+const MAX_WIDTH: usize = 10000;
+
+/**
+ * This function reads a number from the start of the characters, or None if they do not start with a digit.
+ * If the number is bigger than MAX_WIDTH, it will return an error.
+ */
+fn read_number(chars: &mut std::iter::Peekable<std::str::Chars>, text: &str) -> Result<Option<usize>, String> {
+    let mut number = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        let next = number.unwrap_or(0usize).checked_mul(10).and_then(|number| number.checked_add(digit as usize));
+        match next {
+            Some(next) if next <= MAX_WIDTH => number = Some(next),
+            _ => return Err(format!("Invalid format specifier {}, a width or precision can be at most {}", text, MAX_WIDTH)),
+        }
+        chars.next();
+    }
+    Ok(number)
+}
+
+/**
+ * This function reads the specifier of a placeholder.
+ * If it is not a valid specifier, it will return an error.
+ */
+fn parse_spec(text: &str) -> Result<Spec, String> {
+    let mut spec = Spec::default();
+    let characters = text.chars().collect::<Vec<char>>();
+    let is_align = |c: Option<&char>| matches!(c, Some('<' | '>' | '^'));
+    let mut start = 0;
+    if is_align(characters.get(1)) {
+        spec.fill = Some(characters[0]);
+        spec.align = Some(characters[1]);
+        start = 2;
+    } else if is_align(characters.first()) {
+        spec.align = Some(characters[0]);
+        start = 1;
+    }
+    let rest = characters[start..].iter().collect::<String>();
+    let mut chars = rest.chars().peekable();
+    if chars.peek() == Some(&'0') {
+        spec.zero = true;
+        chars.next();
+    }
+    spec.width = read_number(&mut chars, text)?.unwrap_or(0);
+    if chars.peek() == Some(&'.') {
+        chars.next();
+        spec.precision = Some(read_number(&mut chars, text)?.ok_or(format!("Invalid format specifier {}, expected a precision after the dot", text))?);
+    }
+    if chars.next().is_some() {
+        return Err(format!("Invalid format specifier {}", text));
+    }
+    Ok(spec)
+}
+
+/**
+ * This function splits a template into text and placeholders.
+ * If a placeholder is not closed or a } is not escaped, it will return an error.
+ */
+fn parse_template(template: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            },
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            },
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => return Err(format!("Invalid template, a placeholder is not closed in {}", template)),
+                    }
+                }
+                let (key, spec) = match field.split_once(':') {
+                    Some((key, spec)) => (key, parse_spec(spec)?),
+                    None => (field.as_str(), Spec::default()),
+                };
+                let key = if key.is_empty() {
+                    Key::Next
+                } else if let Ok(index) = key.parse::<usize>() {
+                    Key::Index(index)
+                } else {
+                    Key::Name(key.to_string())
+                };
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+                pieces.push(Piece::Field(key, spec));
+            },
+            '}' => return Err(format!("Invalid template, }} has to be written as }}}} in {}", template)),
+            c => text.push(c),
+        }
+    }
+    pieces.push(Piece::Text(text));
+    Ok(pieces)
+}
+
+/**
+ * This function returns the text of a value, strings and symbols are written without quotes.
+ */
+fn text(value: &Value) -> String {
+    String::from(value.clone())
+}
+
+/**
+ * This function writes a value the way the specifier says.
+ * A precision can only be used with floats, where it is the number of decimals, and strings, where it is the most characters kept.
+ */
+fn render(value: &Value, spec: &Spec) -> Result<String, String> {
+    let numeric = matches!(value, Value::Int(_) | Value::Float(_));
+    let written = match (value, spec.precision) {
+        (Value::Float(float), Some(precision)) => format!("{:.*}", precision, float),
+        (Value::String(string), Some(precision)) => string.chars().take(precision).collect(),
+        (_, Some(_)) => return Err(format!("Invalid format specifier, a precision can not be used with {}", value.type_name())),
+        (value, None) => text(value),
+    };
+    let length = written.chars().count();
+    if length >= spec.width {
+        return Ok(written);
+    }
+    let missing = spec.width - length;
+    if spec.zero && numeric && spec.align.is_none() {     // zeros go after the sign
+        let (sign, digits) = match written.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", written.as_str()),
+        };
+        return Ok(format!("{}{}{}", sign, "0".repeat(missing), digits));
+    }
+    let fill = spec.fill.unwrap_or(' ').to_string();
+    let align = spec.align.unwrap_or(if numeric { '>' } else { '<' });
+    Ok(match align {
+        '>' => fill.repeat(missing) + &written,
+        '^' => fill.repeat(missing / 2) + &written + &fill.repeat(missing - missing / 2),
+        _ => written + &fill.repeat(missing),
+    })
+}
+
+/**
+ * This function fills the placeholders in a template with the values from a lookup function, and writes the result.
+ */
+fn fill(pieces: &[Piece], mut lookup: impl FnMut(&Key, usize) -> Result<Value, String>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut next = 0;
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => result.push_str(text),
+            Piece::Field(key, spec) => {
+                let value = lookup(key, next)?;
+                if let Key::Next = key {
+                    next += 1;
+                }
+                result.push_str(&render(&value, spec)?);
+            },
+        }
+    }
+    Ok(result)
+}
+
+/**
+ * This function fills the placeholders in the template on top of the stack.
+ * Values for {} and {0} are taken from the stack below the template, in the order they were pushed.
+ * If the template has named placeholders, the value right below it has to be a map with the names,
+ * and the values by position are taken from below the map.
+ */
+pub fn format(stack: &mut Stack) -> Result<(), String> {
+    let template = match stack.pop()? {
+        Value::String(template) => template,
+        _ => return Err("Invalid operation, format expects a template string".to_string()),
+    };
+    let pieces = parse_template(&template)?;
+    let mut positions = 0;
+    let mut named = false;
+    let mut next = 0;
+    for piece in &pieces {
+        match piece {
+            Piece::Field(Key::Next, _) => {
+                next += 1;
+                positions = positions.max(next);
+            },
+            Piece::Field(Key::Index(index), _) => positions = positions.max(index + 1),
+            Piece::Field(Key::Name(_), _) => named = true,
+            Piece::Text(_) => {},
+        }
+    }
+    let map = if named {
+        match stack.pop()? {
+            Value::Map(map) => map,
+            _ => return Err("Invalid operation, format with named placeholders expects a map below the template".to_string()),
+        }
+    } else {
+        Default::default()
+    };
+    if stack.len() < positions {
+        return Err(format!("Invalid operation, format needs {} values but {} are on the stack", positions, stack.len()));
+    }
+    let mut values = Vec::new();
+    for _ in 0..positions {
+        values.push(stack.pop()?);
+    }
+    values.reverse();
+    let result = fill(&pieces, |key, next| match key {
+        Key::Next => Ok(values[next].clone()),
+        Key::Index(index) => Ok(values[*index].clone()),
+        Key::Name(name) => map.get(name).cloned().ok_or(format!("Invalid operation, the map for format has no key {}", name)),
+    })?;
+    stack.push(Value::String(result));
+    Ok(())
+}
+
+/**
+ * This function reads an interpolated string, f" ... ", and fills its placeholders with the values of variables.
+ * Only named placeholders can be used, and the names are looked up like any other word.
+ * If a name is not defined, it will return an error.
+ */
+pub fn interpolate<'a, I>(iter: &mut I, stack: &mut Stack, variables: &variables::Variables) -> Result<(), String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let template = String::from(read_string(iter));
+    let pieces = parse_template(&template)?;
+    let result = fill(&pieces, |key, _| match key {
+        Key::Name(name) => variables.get(name).ok_or(format!("Invalid operation, {} is not defined", name)),
+        _ => Err("Invalid template, an interpolated string can only have named placeholders".to_string()),
+    })?;
+    stack.push(Value::String(result));
+    Ok(())
+}
+
+/**
+ * This function turns any value into a string, written the way it is printed but without quotes around strings.
+ */
+pub fn to_string(stack: &mut Stack) -> Result<(), String> {
+    let value = stack.pop()?;
+    stack.push(Value::String(text(&value)));
+    Ok(())
+}
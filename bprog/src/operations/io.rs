@@ -2,7 +2,7 @@
  * This module contains functions that perform input and output operations.
  * It contains functions for reading and printing values, as well as reading a line of text.
  */
-use std::io;
+use std::{fs, io};
 
 use crate::{stack::Stack, types::Value, variables};
 
@@ -35,4 +35,35 @@ pub fn read(stack: &mut Stack) -> Result<(), String> {
     io::stdin().read_line(&mut input).unwrap();
     stack.push(Value::String(input.trim().to_string()));
     Ok(())
-}
\ No newline at end of file
+}
+
+/**
+ * This function reads the whole text of a file and pushes it to the stack.
+ * If the file can not be read, it will return an error.
+ */
+pub fn read_file(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::String(path) => {
+            let text = fs::read_to_string(&path).map_err(|e| format!("Could not read the file {}: {}", path, e))?;
+            stack.push(Value::String(text));
+            Ok(())
+        },
+        _ => Err("Invalid operation, readFile expects a path".to_string()),
+    }
+}
+
+/**
+ * This function writes a string to a file, text path writeFile.
+ * The file is made if it does not exist, and replaced if it does.
+ * If the file can not be written, it will return an error.
+ */
+pub fn write_file(stack: &mut Stack) -> Result<(), String> {
+    let path = stack.pop()?;
+    let text = stack.pop()?;
+    match (text, path) {
+        (Value::String(text), Value::String(path)) => {
+            fs::write(&path, text).map_err(|e| format!("Could not write the file {}: {}", path, e))
+        },
+        _ => Err("Invalid operation, writeFile expects a string and a path".to_string()),
+    }
+}
@@ -0,0 +1,373 @@
+/*!
+ * This module contains functions that read and write JSON.
+ * Objects are maps, arrays are lists, numbers without a fraction or exponent are integers and other numbers are floats.
+ * null is the symbol null, since there is no value for nothing.
+ *
+ * The parser and writer are written here, so they follow the JSON standard but do not need another crate.
+ */
+
+use std::collections::BTreeMap;
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * The most arrays and objects that can be inside each other, so deep nesting is an error instead of a stack overflow.
+ */
+const MAX_NESTING: usize = 1000;
+
+/**
+ * A Parser reads one JSON value from the characters of a string, and keeps track of where it is and how deeply nested.
+ */
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /**
+     * This function makes an error that tells where in the text it went wrong.
+     */
+    fn error(&self, message: &str) -> String {
+        format!("Invalid JSON at position {}: {}", self.position, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.get(self.position), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    /**
+     * This function reads the expected character after any whitespace.
+     * If it is another character, it will return an error.
+     */
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.chars.get(self.position) != Some(&expected) {
+            return Err(self.error(&format!("expected {}", expected)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /**
+     * This function reads a word like true, false or null.
+     */
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        for expected in word.chars() {
+            if self.chars.get(self.position) != Some(&expected) {
+                return Err(self.error(&format!("expected {}", word)));
+            }
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    /**
+     * This function reads any JSON value.
+     */
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.position) {
+            Some('{' | '[') if self.depth >= MAX_NESTING => Err(self.error("too deeply nested")),
+            Some('{') => self.nested(Parser::object),
+            Some('[') => self.nested(Parser::array),
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('t') => self.keyword("true", Value::Boolean(true)),
+            Some('f') => self.keyword("false", Value::Boolean(false)),
+            Some('n') => self.keyword("null", Value::Symbol("null".to_string())),
+            Some('-' | '0'..='9') => self.number(),
+            Some(c) => Err(self.error(&format!("unexpected character {}", c))),
+            None => Err(self.error("unexpected end of the text")),
+        }
+    }
+
+    /**
+     * This function reads an object or array one level deeper.
+     */
+    fn nested(&mut self, read: fn(&mut Parser) -> Result<Value, String>) -> Result<Value, String> {
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.position += 1;
+        let mut map = BTreeMap::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&'}') {
+            self.position += 1;
+            return Ok(Value::Map(map));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.get(self.position) != Some(&'"') {
+                return Err(self.error("expected a string as the key"));
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            let value = self.value()?;
+            map.insert(key, value);     // if a key is there twice the last value is kept
+            self.skip_whitespace();
+            match self.chars.get(self.position) {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Map(map));
+                },
+                _ => return Err(self.error("expected , or }")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.position += 1;
+        let mut list = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&']') {
+            self.position += 1;
+            return Ok(Value::List(list));
+        }
+        loop {
+            list.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.get(self.position) {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::List(list));
+                },
+                _ => return Err(self.error("expected , or ]")),
+            }
+        }
+    }
+
+    /**
+     * This function reads the four hex digits after \u.
+     */
+    fn hex(&mut self) -> Result<u32, String> {
+        let digits = self.chars.get(self.position..self.position + 4).ok_or(self.error("expected four hex digits"))?;
+        let code = u32::from_str_radix(&digits.iter().collect::<String>(), 16).map_err(|_| self.error("expected four hex digits"))?;
+        self.position += 4;
+        Ok(code)
+    }
+
+    /**
+     * This function reads a string, with the escapes turned into the characters they stand for.
+     * Characters outside the basic plane can be written as two \u escapes, a surrogate pair.
+     */
+    fn string(&mut self) -> Result<String, String> {
+        self.position += 1;
+        let mut string = String::new();
+        loop {
+            let c = *self.chars.get(self.position).ok_or(self.error("a string is not closed"))?;
+            self.position += 1;
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = *self.chars.get(self.position).ok_or(self.error("a string is not closed"))?;
+                    self.position += 1;
+                    match escaped {
+                        '"' | '\\' | '/' => string.push(escaped),
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{c}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => {
+                            let mut code = self.hex()?;
+                            if (0xD800..0xDC00).contains(&code) && self.chars.get(self.position..self.position + 2) == Some(&['\\', 'u']) {
+                                self.position += 2;
+                                let low = self.hex()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("expected the second half of a surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            string.push(char::from_u32(code).ok_or(self.error("invalid unicode escape"))?);
+                        },
+                        other => return Err(self.error(&format!("invalid escape \\{}", other))),
+                    }
+                },
+                c if (c as u32) < 0x20 => return Err(self.error("control characters in a string have to be escaped")),
+                c => string.push(c),
+            }
+        }
+    }
+
+    /**
+     * This function reads a number, an integer if it has no fraction or exponent and fits, and a float otherwise.
+     * If the number is too big for a float, it will return an error.
+     */
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.position;
+        let digits = |parser: &mut Parser| {
+            let start = parser.position;
+            while parser.chars.get(parser.position).is_some_and(|c| c.is_ascii_digit()) {
+                parser.position += 1;
+            }
+            parser.position > start
+        };
+        if self.chars.get(self.position) == Some(&'-') {
+            self.position += 1;
+        }
+        let integer_start = self.position;
+        if !digits(self) {
+            return Err(self.error("expected a digit"));
+        }
+        if self.chars[integer_start] == '0' && self.position - integer_start > 1 {
+            self.position = integer_start;
+            return Err(self.error("a number can not start with 0"));
+        }
+        let mut integer = true;
+        if self.chars.get(self.position) == Some(&'.') {
+            self.position += 1;
+            integer = false;
+            if !digits(self) {
+                return Err(self.error("expected a digit after the dot"));
+            }
+        }
+        if matches!(self.chars.get(self.position), Some('e' | 'E')) {
+            self.position += 1;
+            integer = false;
+            if matches!(self.chars.get(self.position), Some('+' | '-')) {
+                self.position += 1;
+            }
+            if !digits(self) {
+                return Err(self.error("expected a digit in the exponent"));
+            }
+        }
+        let text = self.chars[start..self.position].iter().collect::<String>();
+        if integer && let Ok(int) = text.parse::<i64>() {
+            return Ok(Value::Int(int));
+        }
+        let float = text.parse::<f64>().map_err(|_| self.error("invalid number"))?;
+        if !float.is_finite() {     // toJson could not write it back
+            self.position = start;
+            return Err(self.error("the number is too big"));
+        }
+        Ok(Value::Float(float))
+    }
+}
+
+/**
+ * This function reads a JSON string and pushes the value.
+ * If the string is not valid JSON, it will return an error that tells where.
+ */
+pub fn parse_json(stack: &mut Stack) -> Result<(), String> {
+    let text = match stack.pop()? {
+        Value::String(text) => text,
+        _ => return Err("Invalid operation, parseJson expects a string".to_string()),
+    };
+    let mut parser = Parser { chars: text.chars().collect(), position: 0, depth: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < parser.chars.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    stack.push(value);
+    Ok(())
+}
+
+/**
+ * This function writes a string with quotes, and escapes the characters that have to be escaped.
+ */
+fn write_string(string: &str, out: &mut String) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/**
+ * This function writes a value as JSON.
+ * If pretty is true, every element of a list or map is on its own line, indented by the depth.
+ * Symbols are written as strings, except null.
+ * If the value has no JSON form, like a block or a float that is not a number, or is nested deeper than MAX_NESTING,
+ * it will return an error.
+ */
+fn write_json(value: &Value, pretty: bool, depth: usize, out: &mut String) -> Result<(), String> {
+    let newline = |out: &mut String, depth: usize| {
+        if pretty {
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+        }
+    };
+    if matches!(value, Value::List(_) | Value::Map(_)) && depth >= MAX_NESTING {
+        return Err(format!("Invalid operation, toJson can not write lists and maps nested more than {} deep", MAX_NESTING));
+    }
+    match value {
+        Value::Int(int) => out.push_str(&int.to_string()),
+        Value::Float(float) if float.is_finite() => {
+            let written = float.to_string();
+            out.push_str(&written);
+            if !written.contains(['.', 'e']) {      // so it is read back as a float
+                out.push_str(".0");
+            }
+        },
+        Value::Float(float) => return Err(format!("Invalid operation, toJson can not write {}, JSON has no such number", float)),
+        Value::Boolean(boolean) => out.push_str(&boolean.to_string()),
+        Value::String(string) => write_string(string, out),
+        Value::Symbol(symbol) if symbol == "null" => out.push_str("null"),
+        Value::Symbol(symbol) => write_string(symbol, out),
+        Value::List(list) => {
+            if list.is_empty() {
+                out.push_str("[]");
+                return Ok(());
+            }
+            out.push('[');
+            for (i, element) in list.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_json(element, pretty, depth + 1, out)?;
+            }
+            newline(out, depth);
+            out.push(']');
+        },
+        Value::Map(map) => {
+            if map.is_empty() {
+                out.push_str("{}");
+                return Ok(());
+            }
+            out.push('{');
+            for (i, (key, element)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_string(key, out);
+                out.push_str(if pretty { ": " } else { ":" });
+                write_json(element, pretty, depth + 1, out)?;
+            }
+            newline(out, depth);
+            out.push('}');
+        },
+        _ => return Err(format!("Invalid operation, toJson can not write a {}, it has no JSON form", value.type_name())),
+    }
+    Ok(())
+}
+
+/**
+ * This function writes the value on top of the stack as a JSON string.
+ * With pretty it is written over several lines and indented with two spaces, otherwise it is written on one line.
+ */
+pub fn to_json(stack: &mut Stack, pretty: bool) -> Result<(), String> {
+    let value = stack.pop()?;
+    let mut out = String::new();
+    write_json(&value, pretty, 0, &mut out)?;
+    stack.push(Value::String(out));
+    Ok(())
+}
@@ -1,11 +1,13 @@
-/**
+/*!
  * This module contains functions that perform list operations.
- * It contains functions for getting the head, tail, empty, length, and appending lists.
+ * It contains functions for getting the head, tail, empty, length, and appending lists,
+ * and the rest of the list library, like zip, flatten, foldr, scan, find, unique, groupBy, partition, chunk and windows.
+ * The words that run a block read it from the code after the word, and their With variants take it from the top of the stack.
  */
 
-use crate::{functions, stack::Stack, types::Value, variables};
+use crate::{functions, sequence::Sequence, signal::Signal, stack::Stack, types::Value, variables};
 
-use super::flow::read_block;
+use super::flow::block_argument;
 
 /**
  * This function returns the first element of a list or string.
@@ -68,7 +70,7 @@ pub fn cons(stack: &mut Stack) -> Result<(), String> {
             Value::cons(&mut list, value)?;
             stack.push(Value::List(list));
         },
-        _ => return Err(format!("Invalid operation")),
+        _ => return Err("Invalid operation".to_string()),
     }
     Ok(())
 }
@@ -86,7 +88,7 @@ pub fn append(stack: &mut Stack) -> Result<(), String> {
             Value::append(&mut a, b)?;
             stack.push(Value::List(a));
         },
-        _ => return Err(format!("Invalid operation")),
+        _ => return Err("Invalid operation".to_string()),
     }
     Ok(())
 }
@@ -97,27 +99,18 @@ pub fn append(stack: &mut Stack) -> Result<(), String> {
  * As the each can be given a code block after itself it has to be called with an iterator.
  * If the value is not a list or block, it will return an error.
  */
-pub fn each<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+pub fn each<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
 where
     I: Iterator<Item = &'a String>,
 {
+    let block = block_argument(iter, stack, from_stack)?;
     let list = stack.pop()?;
     match list {
-        Value::List(_) => {
-            if let Some(token) = iter.next() {
-                if token == "{" {
-                    let block = read_block(iter)?;
-                    list.each(stack, block, variables, functions)?;
-                    Ok(())
-                } else {
-                    list.each(stack, Value::Block(vec!(token.to_string())), variables, functions)?;
-                    Ok(())
-                }
-            } else {
-                Err(format!("Invalid operation"))
-            }
+        Value::List(_) | Value::Seq(_) => {
+            list.each(stack, block, variables, functions)?;
+            Ok(())
         }
-        _ => return Err(format!("Invalid operation")),
+        _ => Err("Invalid operation".into()),
     }    
 }
 
@@ -125,29 +118,25 @@ where
  * This function applies a block to each element in a list and returns a new list with the results.
  * It takes a stack, a block, a variables object, and a functions object as arguments.
  * As the map can be given a code block after itself it has to be called with an iterator.
- * If the value is not a list or block, it will return an error.
+ * A sequence is mapped lazily, the result is a new sequence.
+ * If the value is not a list, sequence or block, it will return an error.
  */
-pub fn map<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+pub fn map<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
 where
     I: Iterator<Item = &'a String>,
 {
+    let block = block_argument(iter, stack, from_stack)?;
     let list = stack.pop()?;
     match list {
         Value::List(_) => {
-            if let Some(token) = iter.next() {
-                if token == "{" {
-                    let block = read_block(iter)?;
-                    list.map(stack, block, variables, functions)?;
-                    Ok(())
-                } else {
-                    list.map(stack, Value::Block(vec!(token.to_string())), variables, functions)?;
-                    Ok(())
-                }
-            } else {
-                Err(format!("Invalid operation"))
-            }
+            list.map(stack, block, variables, functions)?;
+            Ok(())
         }
-        _ => return Err(format!("Invalid operation")),
+        Value::Seq(sequence) => {       // a sequence is mapped lazily, the block runs when the elements are needed
+            stack.push(Value::Seq(Sequence::Map(Box::new(sequence), Box::new(block))));
+            Ok(())
+        }
+        _ => Err("Invalid operation".into()),
     }    
 }
 
@@ -157,27 +146,308 @@ where
  * As the foldl can be given a code block after itself it has to be called with an iterator.
  * If the value is not a list or block, it will return an error.
  */
-pub fn foldl<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String>
+pub fn foldl<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
 where
     I: Iterator<Item = &'a String>,
 {
+    let block = block_argument(iter, stack, from_stack)?;
     let start_value = stack.pop()?;
     let list = stack.pop()?;
     match list {
-        Value::List(_) => {
-            if let Some(token) = iter.next() {
-                if token == "{" {
-                    let block = read_block(iter)?;
-                    list.foldl(stack, block, start_value, variables, functions)?;
-                    Ok(())
-                } else {
-                    list.foldl(stack, Value::Block(vec!(token.to_string())), start_value, variables, functions)?;
-                    Ok(())
-                }
-            } else {
-                Err(format!("Invalid operation"))
-            }
+        Value::List(_) | Value::Seq(_) => {
+            list.foldl(stack, block, start_value, variables, functions)?;
+            Ok(())
         }
-        _ => return Err(format!("Invalid operation")),
+        _ => Err("Invalid operation".into()),
     }    
-}
\ No newline at end of file
+}
+/**
+ * This function pops a list from the stack.
+ * If the value is not a list, it will return an error.
+ */
+fn pop_list(stack: &mut Stack, word: &str) -> Result<Vec<Value>, String> {
+    match stack.pop()? {
+        Value::List(list) => Ok(list),
+        value => Err(format!("Invalid operation, {} expects a list, got {}", word, value)),
+    }
+}
+
+/**
+ * This function pops a size for chunk and windows from the stack.
+ * If the value is not an integer above zero, it will return an error.
+ */
+fn pop_size(stack: &mut Stack, word: &str) -> Result<usize, String> {
+    match stack.pop()? {
+        Value::Int(size) if size > 0 => Ok(size as usize),
+        value => Err(format!("Invalid operation, {} expects a size above zero, got {}", word, value)),
+    }
+}
+
+/**
+ * This function runs a block on some values and returns the value it pushes.
+ */
+fn apply(block: &Value, values: Vec<Value>, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<Value, Signal> {
+    for value in values {
+        stack.push(value);
+    }
+    block.exec(stack, variables, functions)?;
+    Ok(stack.pop()?)
+}
+
+/**
+ * This function runs a block on a value and returns the boolean it pushes.
+ * If the block does not push a boolean, it will return an error.
+ */
+fn test(block: &Value, value: Value, word: &str, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<bool, Signal> {
+    match apply(block, vec![value], stack, variables, functions)? {
+        Value::Boolean(result) => Ok(result),
+        _ => Err(format!("Invalid operation, the block of {} has to push a boolean", word).into()),
+    }
+}
+
+/**
+ * This function pushes the element at an index in a list, 0 is the first element.
+ * If the index is out of range, it will return an error.
+ */
+pub fn nth(stack: &mut Stack) -> Result<(), String> {
+    let index = stack.pop()?;
+    let list = pop_list(stack, "nth")?;
+    match index {
+        Value::Int(index) if index >= 0 && (index as usize) < list.len() => {
+            stack.push(list[index as usize].clone());
+            Ok(())
+        },
+        Value::Int(index) => Err(format!("Invalid index, {} is out of range for a list of length {}", index, list.len())),
+        _ => Err("Invalid operation, nth expects an integer index".to_string()),
+    }
+}
+
+/**
+ * This function pairs up the elements of two lists, the result is as long as the shortest list.
+ */
+pub fn zip(stack: &mut Stack) -> Result<(), String> {
+    let second = pop_list(stack, "zip")?;
+    let first = pop_list(stack, "zip")?;
+    let pairs = first.into_iter().zip(second).map(|(a, b)| Value::List(vec![a, b])).collect();
+    stack.push(Value::List(pairs));
+    Ok(())
+}
+
+/**
+ * This function splits a list of pairs into a list of the first elements and a list of the second elements.
+ * If an element is not a pair, it will return an error.
+ */
+pub fn unzip(stack: &mut Stack) -> Result<(), String> {
+    let list = pop_list(stack, "unzip")?;
+    let mut firsts = Vec::new();
+    let mut seconds = Vec::new();
+    for pair in list {
+        match pair {
+            Value::List(mut pair) if pair.len() == 2 => {
+                seconds.push(pair.pop().unwrap());
+                firsts.push(pair.pop().unwrap());
+            },
+            _ => return Err(format!("Invalid operation, unzip expects a list of pairs, not {}", pair)),
+        }
+    }
+    stack.push(Value::List(firsts));
+    stack.push(Value::List(seconds));
+    Ok(())
+}
+
+/**
+ * This function makes one list of a list of lists, elements that are not lists are kept as they are.
+ * Only one level is flattened.
+ */
+pub fn flatten(stack: &mut Stack) -> Result<(), String> {
+    let list = pop_list(stack, "flatten")?;
+    let mut flat = Vec::new();
+    for value in list {
+        match value {
+            Value::List(inner) => flat.extend(inner),
+            value => flat.push(value),
+        }
+    }
+    stack.push(Value::List(flat));
+    Ok(())
+}
+
+/**
+ * This function folds a list from right to left, the block gets an element and the result so far.
+ */
+pub fn foldr<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let mut result = stack.pop()?;
+    let list = pop_list(stack, "foldr")?;
+    for value in list.into_iter().rev() {
+        result = apply(&block, vec![value, result], stack, variables, functions)?;
+    }
+    stack.push(result);
+    Ok(())
+}
+
+/**
+ * This function folds a list from left to right like foldl, but pushes a list of all the results on the way.
+ * The list starts with the start value.
+ */
+pub fn scan<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let mut result = stack.pop()?;
+    let list = pop_list(stack, "scan")?;
+    let mut results = vec![result.clone()];
+    for value in list {
+        result = apply(&block, vec![result, value], stack, variables, functions)?;
+        results.push(result.clone());
+    }
+    stack.push(Value::List(results));
+    Ok(())
+}
+
+/**
+ * This function pushes true if the block pushes true for any element of a list or sequence.
+ * It stops at the first element that does, so it also works on sequences that never end if one does.
+ */
+pub fn any<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let mut items = stack.pop()?.into_sequence().ok_or("Invalid operation, any expects a list or sequence")?;
+    while let Some(value) = items.next(stack, variables, functions)? {
+        if test(&block, value, "any", stack, variables, functions)? {
+            stack.push(Value::Boolean(true));
+            return Ok(());
+        }
+    }
+    stack.push(Value::Boolean(false));
+    Ok(())
+}
+
+/**
+ * This function pushes true if the block pushes true for all the elements of a list or sequence.
+ * It stops at the first element that does not.
+ */
+pub fn all<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let mut items = stack.pop()?.into_sequence().ok_or("Invalid operation, all expects a list or sequence")?;
+    while let Some(value) = items.next(stack, variables, functions)? {
+        if !test(&block, value, "all", stack, variables, functions)? {
+            stack.push(Value::Boolean(false));
+            return Ok(());
+        }
+    }
+    stack.push(Value::Boolean(true));
+    Ok(())
+}
+
+/**
+ * This function pushes the first element of a list or sequence for which the block pushes true.
+ * If there is none, it pushes the symbol none.
+ */
+pub fn find<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let mut items = stack.pop()?.into_sequence().ok_or("Invalid operation, find expects a list or sequence")?;
+    while let Some(value) = items.next(stack, variables, functions)? {
+        if test(&block, value.clone(), "find", stack, variables, functions)? {
+            stack.push(value);
+            return Ok(());
+        }
+    }
+    stack.push(Value::Symbol("none".to_string()));
+    Ok(())
+}
+
+/**
+ * This function removes the elements of a list that are equal to an element before them.
+ */
+pub fn unique(stack: &mut Stack) -> Result<(), String> {
+    let list = pop_list(stack, "unique")?;
+    let mut kept: Vec<Value> = Vec::new();
+    for value in list {
+        if !kept.contains(&value) {
+            kept.push(value);
+        }
+    }
+    stack.push(Value::List(kept));
+    Ok(())
+}
+
+/**
+ * This function groups the elements of a list by the key the block pushes for them.
+ * The result is a list of pairs of a key and a list of the elements with that key, in the order the keys were first seen.
+ */
+pub fn group_by<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let list = pop_list(stack, "groupBy")?;
+    let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+    for value in list {
+        let key = apply(&block, vec![value.clone()], stack, variables, functions)?;
+        match groups.iter_mut().find(|(group, _)| *group == key) {
+            Some((_, members)) => members.push(value),
+            None => groups.push((key, vec![value])),
+        }
+    }
+    let groups = groups.into_iter().map(|(key, members)| Value::List(vec![key, Value::List(members)])).collect();
+    stack.push(Value::List(groups));
+    Ok(())
+}
+
+/**
+ * This function splits a list into the elements for which the block pushes true and the ones for which it pushes false.
+ * Both lists are pushed, the one with true first.
+ */
+pub fn partition<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let list = pop_list(stack, "partition")?;
+    let mut matching = Vec::new();
+    let mut rest = Vec::new();
+    for value in list {
+        if test(&block, value.clone(), "partition", stack, variables, functions)? {
+            matching.push(value);
+        } else {
+            rest.push(value);
+        }
+    }
+    stack.push(Value::List(matching));
+    stack.push(Value::List(rest));
+    Ok(())
+}
+
+/**
+ * This function splits a list into lists of size elements, the last one can be shorter.
+ */
+pub fn chunk(stack: &mut Stack) -> Result<(), String> {
+    let size = pop_size(stack, "chunk")?;
+    let list = pop_list(stack, "chunk")?;
+    stack.push(Value::List(list.chunks(size).map(|chunk| Value::List(chunk.to_vec())).collect()));
+    Ok(())
+}
+
+/**
+ * This function makes a list of every run of size elements that follow each other in a list.
+ * A list shorter than size has no windows.
+ */
+pub fn windows(stack: &mut Stack) -> Result<(), String> {
+    let size = pop_size(stack, "windows")?;
+    let list = pop_list(stack, "windows")?;
+    stack.push(Value::List(list.windows(size).map(|window| Value::List(window.to_vec())).collect()));
+    Ok(())
+}
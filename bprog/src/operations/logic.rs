@@ -1,4 +1,4 @@
-/**
+/*!
  * This module contains functions that perform logical operations on boolean values.
  * It contains functions for logical AND, OR, and NOT.
  */
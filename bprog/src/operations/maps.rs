@@ -0,0 +1,121 @@
+/*!
+ * This module contains functions that make and use maps.
+ * A map is a record of values by name, the names are strings, and symbols can be used for them too.
+ */
+
+use std::collections::BTreeMap;
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * This function turns a value into the name of a map entry.
+ * Strings and symbols can be used as names, other values will return an error.
+ */
+fn key(value: Value) -> Result<String, String> {
+    match value {
+        Value::String(key) | Value::Symbol(key) => Ok(key),
+        _ => Err(format!("Invalid operation, a map key has to be a string or symbol, not {}", value)),
+    }
+}
+
+/**
+ * This function pops a map from the stack.
+ * If the value is not a map, it will return an error.
+ */
+fn pop_map(stack: &mut Stack, word: &str) -> Result<BTreeMap<String, Value>, String> {
+    match stack.pop()? {
+        Value::Map(map) => Ok(map),
+        _ => Err(format!("Invalid operation, {} expects a map", word)),
+    }
+}
+
+/**
+ * This function turns a list of pairs into a map, every pair is a list with a key and a value.
+ * If a key is used more than once, the last value is kept.
+ * A map is left as it is.
+ */
+pub fn to_map(stack: &mut Stack) -> Result<(), String> {
+    let list = match stack.pop()? {
+        Value::List(list) => list,
+        Value::Map(map) => {
+            stack.push(Value::Map(map));
+            return Ok(());
+        },
+        _ => return Err("Invalid operation, toMap expects a list of pairs".to_string()),
+    };
+    let mut map = BTreeMap::new();
+    for pair in list {
+        match pair {
+            Value::List(mut pair) if pair.len() == 2 => {
+                let value = pair.pop().unwrap();
+                map.insert(key(pair.pop().unwrap())?, value);
+            },
+            _ => return Err(format!("Invalid operation, toMap expects pairs of a key and a value, not {}", pair)),
+        }
+    }
+    stack.push(Value::Map(map));
+    Ok(())
+}
+
+/**
+ * This function pushes the value for a key in a map.
+ * If the key is not in the map, it will return an error.
+ */
+pub fn get(stack: &mut Stack) -> Result<(), String> {
+    let key = key(stack.pop()?)?;
+    let map = pop_map(stack, "get")?;
+    let value = map.get(&key).cloned().ok_or(format!("The map has no key {}", key))?;
+    stack.push(value);
+    Ok(())
+}
+
+/**
+ * This function pushes a new map with a key set to a value.
+ */
+pub fn put(stack: &mut Stack) -> Result<(), String> {
+    let value = stack.pop()?;
+    let key = key(stack.pop()?)?;
+    let mut map = pop_map(stack, "put")?;
+    map.insert(key, value);
+    stack.push(Value::Map(map));
+    Ok(())
+}
+
+/**
+ * This function pushes a new map without a key, nothing happens if the key is not in the map.
+ */
+pub fn remove(stack: &mut Stack) -> Result<(), String> {
+    let key = key(stack.pop()?)?;
+    let mut map = pop_map(stack, "remove")?;
+    map.remove(&key);
+    stack.push(Value::Map(map));
+    Ok(())
+}
+
+/**
+ * This function pushes true if a key is in a map, false otherwise.
+ */
+pub fn has_key(stack: &mut Stack) -> Result<(), String> {
+    let key = key(stack.pop()?)?;
+    let map = pop_map(stack, "hasKey")?;
+    stack.push(Value::Boolean(map.contains_key(&key)));
+    Ok(())
+}
+
+/**
+ * This function pushes a list of the keys in a map, in sorted order.
+ */
+pub fn keys(stack: &mut Stack) -> Result<(), String> {
+    let map = pop_map(stack, "keys")?;
+    stack.push(Value::List(map.into_keys().map(Value::String).collect()));
+    Ok(())
+}
+
+/**
+ * This function pushes a list of the values in a map, in the order of their keys.
+ */
+pub fn values(stack: &mut Stack) -> Result<(), String> {
+    let map = pop_map(stack, "values")?;
+    stack.push(Value::List(map.into_values().collect()));
+    Ok(())
+}
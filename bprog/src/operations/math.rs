@@ -0,0 +1,227 @@
+/*!
+ * This module contains the math library and the bitwise operations.
+ *
+ * Integer operations never wrap around, if the result does not fit in an integer it is an error.
+ * Float operations follow IEEE 754, so they can give NaN or infinity, like the square root of a negative number or the log of zero.
+ * Words that turn a float into an integer, like floor and round, fail on NaN, infinity and floats that are too big.
+ * Words that work on floats also take integers, which are turned into floats first.
+ */
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * This function pops an integer from the stack.
+ * If the value is not an integer, it will return an error.
+ */
+fn pop_int(stack: &mut Stack, word: &str) -> Result<i64, String> {
+    match stack.pop()? {
+        Value::Int(value) => Ok(value),
+        value => Err(format!("Invalid operation, {} expects an integer, got {}", word, value)),
+    }
+}
+
+/**
+ * This function pops a number from the stack as a float.
+ * If the value is not a number, it will return an error.
+ */
+fn pop_float(stack: &mut Stack, word: &str) -> Result<f64, String> {
+    match stack.pop()? {
+        Value::Int(value) => Ok(value as f64),
+        Value::Float(value) => Ok(value),
+        value => Err(format!("Invalid operation, {} expects a number, got {}", word, value)),
+    }
+}
+
+/**
+ * This function runs a float function on the number on the stack and pushes the result as a float.
+ * It is used by sqrt, exp, log and the trigonometric functions.
+ */
+pub fn float_function(stack: &mut Stack, word: &str, function: fn(f64) -> f64) -> Result<(), String> {
+    let value = pop_float(stack, word)?;
+    stack.push(Value::Float(function(value)));
+    Ok(())
+}
+
+/**
+ * This function pushes the angle of the point y x, the y value is below the x value on the stack.
+ */
+pub fn atan2(stack: &mut Stack) -> Result<(), String> {
+    let x = pop_float(stack, "atan2")?;
+    let y = pop_float(stack, "atan2")?;
+    stack.push(Value::Float(y.atan2(x)));
+    Ok(())
+}
+
+/**
+ * This function runs an operation on two integers, or on two floats if one of them is a float.
+ * The integer operation returns None if the result does not fit, then it will return an error.
+ */
+fn numeric(stack: &mut Stack, word: &str, int: fn(i64, i64) -> Result<Option<i64>, String>, float: fn(f64, f64) -> Result<f64, String>) -> Result<(), String> {
+    let b = stack.pop()?;
+    let a = stack.pop()?;
+    let result = match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Value::Int(int(a, b)?.ok_or(format!("Integer overflow in {}", word))?),
+        (Value::Int(a), Value::Float(b)) => Value::Float(float(a as f64, b)?),
+        (Value::Float(a), Value::Int(b)) => Value::Float(float(a, b as f64)?),
+        (Value::Float(a), Value::Float(b)) => Value::Float(float(a, b)?),
+        _ => return Err(format!("Invalid operation, {} expects two numbers", word)),
+    };
+    stack.push(result);
+    Ok(())
+}
+
+/**
+ * This function pushes the modulo of two numbers, the result has the same sign as the divisor, -7 3 mod is 2.
+ * If the divisor is zero, it will return an error.
+ */
+pub fn modulo(stack: &mut Stack) -> Result<(), String> {
+    numeric(stack, "mod",
+        |a, b| if b == 0 { Err("Division by zero".to_string()) } else { Ok(a.checked_rem(b).map(|r| if r != 0 && (r < 0) != (b < 0) { r + b } else { r })) },
+        |a, b| if b == 0.0 { Err("Division by zero".to_string()) } else { Ok(a - b * (a / b).floor()) })
+}
+
+/**
+ * This function pushes the remainder of two numbers, the result has the same sign as the dividend, -7 3 rem is -1.
+ * If the divisor is zero, it will return an error.
+ */
+pub fn remainder(stack: &mut Stack) -> Result<(), String> {
+    numeric(stack, "rem",
+        |a, b| if b == 0 { Err("Division by zero".to_string()) } else { Ok(a.checked_rem(b)) },
+        |a, b| if b == 0.0 { Err("Division by zero".to_string()) } else { Ok(a % b) })
+}
+
+/**
+ * This function pushes a number raised to a power, base exponent pow.
+ * For integers the exponent can not be negative, and a result that does not fit is an error.
+ */
+pub fn pow(stack: &mut Stack) -> Result<(), String> {
+    numeric(stack, "pow",
+        |a, b| match u32::try_from(b) {
+            Ok(b) => Ok(a.checked_pow(b)),
+            Err(_) if b < 0 => Err("Invalid operation, pow with integers expects an exponent that is zero or more".to_string()),
+            Err(_) => Ok(match a { 0 | 1 => Some(a), -1 => Some(if b % 2 == 0 { 1 } else { -1 }), _ => None }),
+        },
+        |a, b| Ok(a.powf(b)))
+}
+
+/**
+ * This function pushes the smallest or the biggest of two numbers.
+ * If one of them is NaN, the result is NaN.
+ */
+pub fn min_max(stack: &mut Stack, word: &str, biggest: bool) -> Result<(), String> {
+    let b = stack.pop()?;
+    let a = stack.pop()?;
+    if !matches!(a, Value::Int(_) | Value::Float(_)) || !matches!(b, Value::Int(_) | Value::Float(_)) {
+        return Err(format!("Invalid operation, {} expects two numbers", word));
+    }
+    let result = match a.partial_cmp(&b) {
+        None => Value::Float(f64::NAN),
+        Some(ordering) if ordering.is_lt() != biggest => a,
+        Some(_) => b,
+    };
+    stack.push(result);
+    Ok(())
+}
+
+/**
+ * This function pushes the absolute value of a number.
+ * The absolute value of the smallest integer does not fit, so it will return an error.
+ */
+pub fn abs(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Int(value) => stack.push(Value::Int(value.checked_abs().ok_or("Integer overflow in abs")?)),
+        Value::Float(value) => stack.push(Value::Float(value.abs())),
+        value => return Err(format!("Invalid operation, abs expects a number, got {}", value)),
+    }
+    Ok(())
+}
+
+/**
+ * This function rounds a number to an integer with a rounding function, it is used by floor, ceil and round.
+ * An integer is left as it is. If the float is NaN, infinite or too big for an integer, it will return an error.
+ */
+pub fn to_integer(stack: &mut Stack, word: &str, rounding: fn(f64) -> f64) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Int(value) => stack.push(Value::Int(value)),
+        Value::Float(value) => {
+            let rounded = rounding(value);
+            // i64::MAX as f64 is 2^63, which is just too big, so the check has to be strictly below it
+            if !rounded.is_finite() || rounded < i64::MIN as f64 || rounded >= i64::MAX as f64 {
+                return Err(format!("Invalid operation, {} of {} does not fit in an integer", word, value));
+            }
+            stack.push(Value::Int(rounded as i64));
+        },
+        value => return Err(format!("Invalid operation, {} expects a number, got {}", word, value)),
+    }
+    Ok(())
+}
+
+/**
+ * This function returns the greatest common divisor of two integers, it is always zero or more.
+ */
+fn gcd_of(a: i64, b: i64) -> Option<i64> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    i64::try_from(a).ok()      // only gcd of the smallest integer and 0 or itself does not fit
+}
+
+/**
+ * This function pushes the greatest common divisor of two integers, 0 0 gcd is 0.
+ */
+pub fn gcd(stack: &mut Stack) -> Result<(), String> {
+    let b = pop_int(stack, "gcd")?;
+    let a = pop_int(stack, "gcd")?;
+    stack.push(Value::Int(gcd_of(a, b).ok_or("Integer overflow in gcd")?));
+    Ok(())
+}
+
+/**
+ * This function pushes the least common multiple of two integers, it is 0 if one of them is 0.
+ */
+pub fn lcm(stack: &mut Stack) -> Result<(), String> {
+    let b = pop_int(stack, "lcm")?;
+    let a = pop_int(stack, "lcm")?;
+    if a == 0 || b == 0 {
+        stack.push(Value::Int(0));
+        return Ok(());
+    }
+    let gcd = gcd_of(a, b).ok_or("Integer overflow in lcm")?;
+    let lcm = (a / gcd).checked_mul(b).and_then(|lcm| lcm.checked_abs()).ok_or("Integer overflow in lcm")?;
+    stack.push(Value::Int(lcm));
+    Ok(())
+}
+
+/**
+ * This function runs a bitwise operation on two integers, it is used by bitAnd, bitOr and bitXor.
+ */
+pub fn bitwise(stack: &mut Stack, word: &str, operation: fn(i64, i64) -> i64) -> Result<(), String> {
+    let b = pop_int(stack, word)?;
+    let a = pop_int(stack, word)?;
+    stack.push(Value::Int(operation(a, b)));
+    Ok(())
+}
+
+/**
+ * This function flips all the bits of an integer.
+ */
+pub fn bit_not(stack: &mut Stack) -> Result<(), String> {
+    let value = pop_int(stack, "bitNot")?;
+    stack.push(Value::Int(!value));
+    Ok(())
+}
+
+/**
+ * This function shifts the bits of an integer to the left, or to the right keeping the sign, value amount shl.
+ * If the amount is not between 0 and 63, it will return an error.
+ */
+pub fn shift(stack: &mut Stack, word: &str, left: bool) -> Result<(), String> {
+    let amount = pop_int(stack, word)?;
+    let value = pop_int(stack, word)?;
+    if !(0..64).contains(&amount) {
+        return Err(format!("Invalid operation, {} expects a shift between 0 and 63, got {}", word, amount));
+    }
+    stack.push(Value::Int(if left { value << amount } else { value >> amount }));
+    Ok(())
+}
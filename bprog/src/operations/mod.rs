@@ -2,4 +2,17 @@ pub mod arithmetic;
 pub mod io;
 pub mod logic;
 pub mod flow;
-pub mod lists;
\ No newline at end of file
+pub mod lists;
+pub mod sequences;
+pub mod maps;
+pub mod contracts;
+pub mod combinators;
+pub mod strings;
+pub mod format;
+pub mod regex;
+pub mod sorting;
+pub mod math;
+pub mod random;
+pub mod time;
+pub mod json;
+pub mod csv;
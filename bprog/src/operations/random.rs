@@ -0,0 +1,198 @@
+/*!
+ * This module contains functions that make random numbers and shuffle lists.
+ * The numbers come from a xoshiro256** generator, which is fast and gives the same numbers every time for the same seed.
+ * Without a seed from the seed word or the --seed option, it is seeded from the clock.
+ * There is one generator for the whole program, which generators running on their own thread share.
+ */
+
+use std::{sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * Rng is the state of the xoshiro256** generator.
+ */
+struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /**
+     * This function makes a generator from a seed, the seed is spread out over the state with splitmix64.
+     */
+    fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut state = [0; 4];
+        for part in state.iter_mut() {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            *part = z ^ (z >> 31);
+        }
+        Rng { state }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /**
+     * This function returns a number from 0 up to, but not including, bound.
+     * Numbers from the top of the range that would make some results more likely than others are thrown away.
+     */
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /**
+     * This function returns a float from 0 up to, but not including, 1.
+     */
+    fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+static RNG: Mutex<Option<Rng>> = Mutex::new(None);
+
+/**
+ * This function runs a function with the generator, and seeds it from the clock if it has no seed yet.
+ */
+fn with_rng<T>(function: impl FnOnce(&mut Rng) -> T) -> T {
+    let mut rng = RNG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let rng = rng.get_or_insert_with(|| {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+        Rng::new(nanos)
+    });
+    function(rng)
+}
+
+/**
+ * This function seeds the generator, after this the same numbers come in the same order every time.
+ * It is used by the seed word and the --seed option.
+ */
+pub fn set_seed(seed: i64) {
+    *RNG.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Rng::new(seed as u64));
+}
+
+/**
+ * This function seeds the generator with the integer on the stack.
+ */
+pub fn seed(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Int(seed) => {
+            set_seed(seed);
+            Ok(())
+        },
+        _ => Err("Invalid operation, seed expects an integer".to_string()),
+    }
+}
+
+/**
+ * This function pushes a random integer from low to high, both included.
+ * If low is bigger than high, it will return an error.
+ */
+pub fn random_int(stack: &mut Stack) -> Result<(), String> {
+    let high = stack.pop()?;
+    let low = stack.pop()?;
+    match (low, high) {
+        (Value::Int(low), Value::Int(high)) if low <= high => {
+            let span = high.abs_diff(low);
+            let offset = if span == u64::MAX { with_rng(|rng| rng.next_u64()) } else { with_rng(|rng| rng.below(span + 1)) };
+            stack.push(Value::Int(low.wrapping_add(offset as i64)));
+            Ok(())
+        },
+        (Value::Int(low), Value::Int(high)) => Err(format!("Invalid operation, randomInt expects low to be at most high, got {} {}", low, high)),
+        _ => Err("Invalid operation, randomInt expects two integers".to_string()),
+    }
+}
+
+/**
+ * This function pushes a random float from 0 up to, but not including, 1.
+ */
+pub fn random_float(stack: &mut Stack) -> Result<(), String> {
+    stack.push(Value::Float(with_rng(|rng| rng.float())));
+    Ok(())
+}
+
+/**
+ * This function pops a list from the stack.
+ * If the value is not a list, it will return an error.
+ */
+fn pop_list(stack: &mut Stack, word: &str) -> Result<Vec<Value>, String> {
+    match stack.pop()? {
+        Value::List(list) => Ok(list),
+        value => Err(format!("Invalid operation, {} expects a list, got {}", word, value)),
+    }
+}
+
+/**
+ * This function shuffles the list in place with the Fisher-Yates shuffle, so every order is as likely.
+ * Only the first count places are shuffled, they hold a random sample of the list when it is done.
+ */
+fn shuffle_start(list: &mut [Value], count: usize) {
+    with_rng(|rng| {
+        for i in 0..count.min(list.len().saturating_sub(1)) {
+            let j = i + rng.below((list.len() - i) as u64) as usize;
+            list.swap(i, j);
+        }
+    });
+}
+
+/**
+ * This function pushes a random element of a list.
+ * If the list is empty, it will return an error.
+ */
+pub fn choice(stack: &mut Stack) -> Result<(), String> {
+    let list = pop_list(stack, "choice")?;
+    if list.is_empty() {
+        return Err("Invalid operation, choice expects a list that is not empty".to_string());
+    }
+    let index = with_rng(|rng| rng.below(list.len() as u64)) as usize;
+    stack.push(list[index].clone());
+    Ok(())
+}
+
+/**
+ * This function pushes the list in a random order.
+ */
+pub fn shuffle(stack: &mut Stack) -> Result<(), String> {
+    let mut list = pop_list(stack, "shuffle")?;
+    let count = list.len();
+    shuffle_start(&mut list, count);
+    stack.push(Value::List(list));
+    Ok(())
+}
+
+/**
+ * This function pushes a list of count random elements from a list, no element is picked twice.
+ * If the list has fewer elements than count, it will return an error.
+ */
+pub fn sample(stack: &mut Stack) -> Result<(), String> {
+    let count = match stack.pop()? {
+        Value::Int(count) if count >= 0 => count as usize,
+        _ => return Err("Invalid operation, sample expects a count that is zero or more".to_string()),
+    };
+    let mut list = pop_list(stack, "sample")?;
+    if count > list.len() {
+        return Err(format!("Invalid operation, can not sample {} elements from a list of length {}", count, list.len()));
+    }
+    shuffle_start(&mut list, count);
+    list.truncate(count);
+    stack.push(Value::List(list));
+    Ok(())
+}
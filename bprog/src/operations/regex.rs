@@ -0,0 +1,149 @@
+/*!
+ * This module contains functions that use regular expressions on strings.
+ * The pattern is always on top of the string it is used on, like string pattern reMatch.
+ * Compiled patterns are kept in a cache, so a pattern used in a loop is only compiled once.
+ */
+
+use std::{cell::RefCell, collections::{BTreeMap, HashMap}};
+
+use regex::{Captures, Regex};
+
+use crate::{functions, signal::Signal, stack::Stack, types::Value, variables};
+
+// the cache is cleared when it gets this big, so programs that make many patterns do not use up memory
+const CACHE_SIZE: usize = 256;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/**
+ * This function compiles a pattern, or gets it from the cache if it has been compiled before.
+ * If the pattern is not a valid regular expression, it will return an error.
+ */
+fn compile(pattern: &str) -> Result<Regex, String> {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid regular expression {}: {}", pattern, e))?;
+        if cache.len() >= CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    })
+}
+
+/**
+ * This function pops a pattern and the string below it from the stack, and compiles the pattern.
+ * If the values are not strings, it will return an error.
+ */
+fn pop_pattern(stack: &mut Stack, word: &str) -> Result<(String, Regex), String> {
+    let pattern = stack.pop()?;
+    let string = stack.pop()?;
+    match (string, pattern) {
+        (Value::String(string), Value::String(pattern)) => Ok((string, compile(&pattern)?)),
+        _ => Err(format!("Invalid operation, {} expects a string and a pattern", word)),
+    }
+}
+
+/**
+ * This function turns the groups of a match into a list, the whole match first.
+ * A group that did not take part in the match is an empty string.
+ */
+fn capture_list(captures: &Captures) -> Value {
+    Value::List(captures.iter()
+        .map(|group| Value::String(group.map_or("", |group| group.as_str()).to_string()))
+        .collect())
+}
+
+/**
+ * This function pushes true if the pattern matches somewhere in the string.
+ */
+pub fn is_match(stack: &mut Stack) -> Result<(), String> {
+    let (string, regex) = pop_pattern(stack, "reMatch")?;
+    stack.push(Value::Boolean(regex.is_match(&string)));
+    Ok(())
+}
+
+/**
+ * This function pushes the groups of the first match as a list, or an empty list if there is no match.
+ */
+pub fn find(stack: &mut Stack) -> Result<(), String> {
+    let (string, regex) = pop_pattern(stack, "reFind")?;
+    let found = regex.captures(&string).map_or(Value::List(Vec::new()), |captures| capture_list(&captures));
+    stack.push(found);
+    Ok(())
+}
+
+/**
+ * This function pushes a list with the groups of every match.
+ */
+pub fn find_all(stack: &mut Stack) -> Result<(), String> {
+    let (string, regex) = pop_pattern(stack, "reFindAll")?;
+    let found = regex.captures_iter(&string).map(|captures| capture_list(&captures)).collect();
+    stack.push(Value::List(found));
+    Ok(())
+}
+
+/**
+ * This function pushes a map from the names of the named groups to what they matched in the first match.
+ * Groups that did not take part in the match are left out, and if there is no match the map is empty.
+ */
+pub fn captures(stack: &mut Stack) -> Result<(), String> {
+    let (string, regex) = pop_pattern(stack, "reCaptures")?;
+    let mut map = BTreeMap::new();
+    if let Some(captures) = regex.captures(&string) {
+        for name in regex.capture_names().flatten() {
+            if let Some(group) = captures.name(name) {
+                map.insert(name.to_string(), Value::String(group.as_str().to_string()));
+            }
+        }
+    }
+    stack.push(Value::Map(map));
+    Ok(())
+}
+
+/**
+ * This function replaces every match of a pattern, string pattern replacement reReplace.
+ * The replacement can be a string, where $1 or ${name} is what a group matched,
+ * or a block that gets the list of groups for every match and has to push the string to put in its place.
+ */
+pub fn replace(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    let replacement = stack.pop()?;
+    let (string, regex) = pop_pattern(stack, "reReplace")?;
+    let replaced = match replacement {
+        Value::String(template) => regex.replace_all(&string, template.as_str()).into_owned(),
+        block @ (Value::Block(_) | Value::Closure(..)) => {
+            let mut replaced = String::new();
+            let mut last = 0;
+            for captures in regex.captures_iter(&string) {
+                let whole = captures.get(0).unwrap();
+                replaced.push_str(&string[last..whole.start()]);
+                stack.push(capture_list(&captures));
+                block.exec(stack, variables, functions)?;
+                match stack.pop()? {
+                    Value::String(text) => replaced.push_str(&text),
+                    _ => return Err("Invalid operation, the block of reReplace has to push a string".into()),
+                }
+                last = whole.end();
+            }
+            replaced.push_str(&string[last..]);
+            replaced
+        },
+        _ => return Err("Invalid operation, reReplace expects a string or a block to replace with".into()),
+    };
+    stack.push(Value::String(replaced));
+    Ok(())
+}
+
+/**
+ * This function splits a string at every match of a pattern, and pushes the parts as a list.
+ */
+pub fn split(stack: &mut Stack) -> Result<(), String> {
+    let (string, regex) = pop_pattern(stack, "reSplit")?;
+    stack.push(Value::List(regex.split(&string).map(Value::from).collect()));
+    Ok(())
+}
@@ -0,0 +1,228 @@
+/*!
+ * This module contains functions that make and use lazy sequences.
+ * It contains functions for ranges, iterate, repeat, generate and lines, and for filter, take, drop and collect.
+ * It also contains generator, next and yield for generators, which can be used as sequences with lazy.
+ * map, each and foldl also work on sequences, they are found in the lists module.
+ */
+
+use crate::{functions, generator::{self, Generator}, sequence::Sequence, signal::Signal, stack::Stack, types::Value, variables};
+
+use super::flow::{block_argument, read_argument};
+
+/**
+ * This function makes a sequence of the numbers from start to end, both included.
+ * It counts down if end is smaller than start.
+ * If the values are not integers, it will return an error.
+ */
+pub fn range(stack: &mut Stack) -> Result<(), String> {
+    let end = stack.pop()?;
+    let start = stack.pop()?;
+    match (start, end) {
+        (Value::Int(start), Value::Int(end)) => {
+            let step = if end >= start { 1 } else { -1 };
+            stack.push(Value::Seq(Sequence::Range { next: Some(start), end: Some(end), step }));
+            Ok(())
+        },
+        _ => Err("Invalid operation, range expects two integers".to_string()),
+    }
+}
+
+/**
+ * This function makes a sequence of all the numbers from start and up, it never ends.
+ * If the value is not an integer, it will return an error.
+ */
+pub fn from(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Int(start) => {
+            stack.push(Value::Seq(Sequence::Range { next: Some(start), end: None, step: 1 }));
+            Ok(())
+        },
+        _ => Err("Invalid operation, from expects an integer".to_string()),
+    }
+}
+
+/**
+ * This function makes a sequence that starts with the value on the stack,
+ * and then every next element is made by running the block after it on the element before.
+ * It never ends.
+ */
+pub fn iterate<'a, I>(iter: &mut I, stack: &mut Stack) -> Result<(), String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let start = stack.pop()?;
+    let block = read_argument(iter)?;
+    stack.push(Value::Seq(Sequence::Iterate { next: Box::new(start), block: Box::new(block), started: false }));
+    Ok(())
+}
+
+/**
+ * This function makes a sequence that repeats the value on the stack forever.
+ */
+pub fn repeat(stack: &mut Stack) -> Result<(), String> {
+    let value = stack.pop()?;
+    stack.push(Value::Seq(Sequence::Repeat(Box::new(value))));
+    Ok(())
+}
+
+/**
+ * This function makes a sequence from the block after it.
+ * The block is run every time an element is needed, and has to push the element.
+ * The sequence ends when the block uses break.
+ */
+pub fn generate<'a, I>(iter: &mut I, stack: &mut Stack) -> Result<(), String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = read_argument(iter)?;
+    stack.push(Value::Seq(Sequence::Generate(Box::new(block))));
+    Ok(())
+}
+
+/**
+ * This function makes a sequence of the lines read from the input, it ends when the input ends.
+ */
+pub fn lines(stack: &mut Stack) -> Result<(), String> {
+    stack.push(Value::Seq(Sequence::Lines));
+    Ok(())
+}
+
+/**
+ * This function turns a list or a generator into a sequence, so it can be used with the lazy operations.
+ * If the value is not a list, generator or sequence, it will return an error.
+ */
+pub fn lazy(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::List(list) => stack.push(Value::Seq(Sequence::List(list, 0))),
+        Value::Generator(generator) => stack.push(Value::Seq(Sequence::Generator(generator))),
+        Value::Seq(sequence) => stack.push(Value::Seq(sequence)),
+        _ => return Err("Invalid operation, lazy expects a list or generator".to_string()),
+    }
+    Ok(())
+}
+
+/**
+ * This function makes all the elements of a sequence and pushes them as a list.
+ * A list is left as it is. If the value is not a list or a sequence, it will return an error.
+ */
+pub fn collect(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+    match stack.pop()? {
+        Value::Seq(sequence) => {
+            let list = sequence.collect(stack, variables, functions)?;
+            stack.push(Value::List(list));
+        },
+        Value::List(list) => stack.push(Value::List(list)),
+        _ => return Err("Invalid operation, collect expects a sequence".into()),
+    }
+    Ok(())
+}
+
+/**
+ * This function reads a count for take and drop from the stack.
+ * If the value is not a positive integer, it will return an error.
+ */
+fn pop_count(stack: &mut Stack, word: &str) -> Result<usize, String> {
+    match stack.pop()? {
+        Value::Int(count) if count >= 0 => Ok(count as usize),
+        _ => Err(format!("Invalid operation, {} expects a count that is zero or more", word)),
+    }
+}
+
+/**
+ * This function keeps the first count elements of a list or sequence.
+ * For a sequence the result is a new sequence, for a list it is a list.
+ */
+pub fn take(stack: &mut Stack) -> Result<(), String> {
+    let count = pop_count(stack, "take")?;
+    match stack.pop()? {
+        Value::Seq(sequence) => stack.push(Value::Seq(Sequence::Take(Box::new(sequence), count))),
+        Value::List(list) => stack.push(Value::List(list.into_iter().take(count).collect())),
+        _ => return Err("Invalid operation, take expects a list or sequence".to_string()),
+    }
+    Ok(())
+}
+
+/**
+ * This function removes the first count elements of a list or sequence.
+ * For a sequence the result is a new sequence, for a list it is a list.
+ */
+pub fn drop(stack: &mut Stack) -> Result<(), String> {
+    let count = pop_count(stack, "drop")?;
+    match stack.pop()? {
+        Value::Seq(sequence) => stack.push(Value::Seq(Sequence::Drop(Box::new(sequence), count))),
+        Value::List(list) => stack.push(Value::List(list.into_iter().skip(count).collect())),
+        _ => return Err("Invalid operation, drop expects a list or sequence".to_string()),
+    }
+    Ok(())
+}
+
+/**
+ * This function keeps the elements of a list or sequence for which the block pushes true, the block is after it or on the stack for filterWith.
+ * For a sequence the result is a new sequence that runs the block when the elements are needed,
+ * for a list the block is run on all the elements right away.
+ */
+pub fn filter<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    match stack.pop()? {
+        Value::Seq(sequence) => {
+            stack.push(Value::Seq(Sequence::Filter(Box::new(sequence), Box::new(block))));
+        },
+        Value::List(list) => {
+            let filtered = Sequence::Filter(Box::new(Sequence::List(list, 0)), Box::new(block));
+            let list = filtered.collect(stack, variables, functions)?;
+            stack.push(Value::List(list));
+        },
+        _ => return Err("Invalid operation, filter expects a list or sequence".into()),
+    }
+    Ok(())
+}
+
+/**
+ * This function makes a generator from the block on the stack.
+ * The block is not run before next is used on the generator.
+ * If the value is not a block, it will return an error.
+ */
+pub fn generator(stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
+    match stack.pop()? {
+        block @ (Value::Block(_) | Value::Closure(..)) => {
+            let generator = Generator::new(block, stack, variables, functions)?;
+            stack.push(Value::Generator(generator));
+            Ok(())
+        },
+        _ => Err("Invalid operation, generator expects a block".to_string()),
+    }
+}
+
+/**
+ * This function runs a generator until it yields a value, and pushes the value and true.
+ * When the block of the generator has ended, it pushes only false, so any value can be yielded.
+ * If the value is not a generator, it will return an error.
+ */
+pub fn next(stack: &mut Stack) -> Result<(), Signal> {
+    match stack.pop()? {
+        Value::Generator(generator) => {
+            match generator.resume()? {
+                Some(value) => {
+                    stack.push(value);
+                    stack.push(Value::Boolean(true));
+                },
+                None => stack.push(Value::Boolean(false)),
+            }
+            Ok(())
+        },
+        _ => Err("Invalid operation, next expects a generator".into()),
+    }
+}
+
+/**
+ * This function gives the value on the stack to the one that called next on the generator,
+ * and waits until next is called again.
+ * Outside of a generator, it will return an error.
+ */
+pub fn r#yield(stack: &mut Stack) -> Result<(), Signal> {
+    let value = stack.pop()?;
+    generator::yield_value(value)
+}
@@ -0,0 +1,117 @@
+/*!
+ * This module contains functions that sort lists.
+ * It contains sort, sortBy with a comparator block and sortOn with a key block, and a descending variant of each.
+ * All of them are stable, elements that are equal keep the order they had.
+ *
+ * The sorting is a merge sort written here instead of the sort from the standard library,
+ * so a comparator block that fails or is not consistent gives an error or a strange order, but never a crash.
+ */
+
+use std::cmp::Ordering;
+
+use crate::{functions, signal::Signal, stack::Stack, types::Value, variables};
+
+use super::flow::block_argument;
+
+/**
+ * This function sorts the items with a stable merge sort.
+ * before tells if the first item has to come before the second, it is only asked about items that are not in order yet.
+ */
+fn merge_sort<T>(items: Vec<T>, before: &mut impl FnMut(&T, &T) -> Result<bool, Signal>) -> Result<Vec<T>, Signal> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let left = merge_sort(left, before)?;
+    let right = merge_sort(right, before)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        if before(b, a)? {     // only an element that is strictly before goes first, so equal elements keep their order
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/**
+ * This function pops the list to sort from the stack.
+ * If the value is not a list, it will return an error.
+ */
+fn pop_list(stack: &mut Stack, word: &str) -> Result<Vec<Value>, String> {
+    match stack.pop()? {
+        Value::List(list) => Ok(list),
+        value => Err(format!("Invalid operation, {} expects a list, got {}", word, value)),
+    }
+}
+
+/**
+ * This function returns the order to sort in, the order of the values or the opposite.
+ */
+fn order(ordering: Ordering, descending: bool) -> Ordering {
+    if descending { ordering.reverse() } else { ordering }
+}
+
+/**
+ * This function sorts a list by the total order of the values, so lists with values of different types can be sorted too.
+ */
+pub fn sort(stack: &mut Stack, descending: bool) -> Result<(), Signal> {
+    let list = pop_list(stack, if descending { "sortDesc" } else { "sort" })?;
+    let sorted = merge_sort(list, &mut |a, b| Ok(order(a.total_cmp(b), descending).is_lt()))?;
+    stack.push(Value::List(sorted));
+    Ok(())
+}
+
+/**
+ * This function sorts a list with a comparator block, which gets two elements a and b.
+ * It can push a boolean that is true if a comes before b, like { < },
+ * or an integer that is negative if a comes before b, zero if they are equal and positive if a comes after b.
+ */
+pub fn sort_by<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, descending: bool, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let word = if descending { "sortByDesc" } else { "sortBy" };
+    let block = block_argument(iter, stack, from_stack)?;
+    let list = pop_list(stack, word)?;
+    let sorted = merge_sort(list, &mut |a, b| {
+        let (a, b) = if descending { (b, a) } else { (a, b) };
+        stack.push(a.clone());
+        stack.push(b.clone());
+        block.exec(stack, variables, functions)?;
+        match stack.pop()? {
+            Value::Boolean(before) => Ok(before),
+            Value::Int(ordering) => Ok(ordering < 0),
+            _ => Err(format!("Invalid operation, the block of {} has to push a boolean or an integer", word).into()),
+        }
+    })?;
+    stack.push(Value::List(sorted));
+    Ok(())
+}
+
+/**
+ * This function sorts a list by the key a block pushes for every element.
+ * The block is run once for every element, and the keys are compared by the total order of the values.
+ */
+pub fn sort_on<'a, I>(iter: &mut I, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions, descending: bool, from_stack: bool) -> Result<(), Signal>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let block = block_argument(iter, stack, from_stack)?;
+    let list = pop_list(stack, if descending { "sortOnDesc" } else { "sortOn" })?;
+    let mut keyed = Vec::with_capacity(list.len());
+    for value in list {
+        stack.push(value.clone());
+        block.exec(stack, variables, functions)?;
+        keyed.push((stack.pop()?, value));
+    }
+    let sorted = merge_sort(keyed, &mut |(a, _), (b, _)| Ok(order(a.total_cmp(b), descending).is_lt()))?;
+    stack.push(Value::List(sorted.into_iter().map(|(_, value)| value).collect()));
+    Ok(())
+}
@@ -0,0 +1,189 @@
+/*!
+ * This module contains functions that work on strings.
+ * All indices and lengths are counted in characters, not bytes, so they work the same for any text.
+ */
+
+use crate::{stack::Stack, types::Value};
+
+/**
+ * This function pops a string from the stack.
+ * If the value is not a string, it will return an error.
+ */
+fn pop_string(stack: &mut Stack, word: &str) -> Result<String, String> {
+    match stack.pop()? {
+        Value::String(string) => Ok(string),
+        value => Err(format!("Invalid operation, {} expects a string, got {}", word, value)),
+    }
+}
+
+/**
+ * This function pops an index or a length from the stack.
+ * If the value is not an integer that is zero or more, it will return an error.
+ */
+fn pop_index(stack: &mut Stack, word: &str) -> Result<usize, String> {
+    match stack.pop()? {
+        Value::Int(index) if index >= 0 => Ok(index as usize),
+        value => Err(format!("Invalid index, {} expects an integer that is zero or more, got {}", word, value)),
+    }
+}
+
+/**
+ * This function splits a string by a separator into a list of strings.
+ * An empty separator splits the string into its characters.
+ */
+pub fn split(stack: &mut Stack) -> Result<(), String> {
+    let separator = pop_string(stack, "split")?;
+    let string = pop_string(stack, "split")?;
+    let parts = if separator.is_empty() {
+        string.chars().map(|c| Value::String(c.to_string())).collect()
+    } else {
+        string.split(separator.as_str()).map(Value::from).collect()
+    };
+    stack.push(Value::List(parts));
+    Ok(())
+}
+
+/**
+ * This function joins the elements of a list into one string, with a separator between them.
+ * Elements that are not strings are written the same way as they are printed.
+ */
+pub fn join(stack: &mut Stack) -> Result<(), String> {
+    let separator = pop_string(stack, "join")?;
+    match stack.pop()? {
+        Value::List(list) => {
+            let parts = list.into_iter().map(String::from).collect::<Vec<String>>();
+            stack.push(Value::String(parts.join(&separator)));
+            Ok(())
+        },
+        _ => Err("Invalid operation, join expects a list".to_string()),
+    }
+}
+
+/**
+ * This function takes the part of a string or list from the start index up to, but not including, the end index.
+ * If an index is past the end or the start is after the end, it will return an error.
+ */
+pub fn slice(stack: &mut Stack) -> Result<(), String> {
+    let end = pop_index(stack, "slice")?;
+    let start = pop_index(stack, "slice")?;
+    let (length, kind) = match stack.peek(0) {
+        Some(Value::String(string)) => (string.chars().count(), "string"),
+        Some(Value::List(list)) => (list.len(), "list"),
+        _ => return Err("Invalid operation, slice expects a string or a list".to_string()),
+    };
+    if end > length {
+        return Err(format!("Invalid index, {} is out of range for a {} of length {}", end, kind, length));
+    }
+    if start > end {
+        return Err(format!("Invalid index, the start {} is after the end {}", start, end));
+    }
+    match stack.pop()? {
+        Value::String(string) => stack.push(Value::String(string.chars().skip(start).take(end - start).collect())),
+        Value::List(list) => stack.push(Value::List(list[start..end].to_vec())),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/**
+ * This function replaces every place a string is found in another string with a replacement.
+ * If the string to replace is empty, it will return an error.
+ */
+pub fn replace(stack: &mut Stack) -> Result<(), String> {
+    let replacement = pop_string(stack, "replace")?;
+    let pattern = pop_string(stack, "replace")?;
+    let string = pop_string(stack, "replace")?;
+    if pattern.is_empty() {
+        return Err("Invalid operation, replace can not replace an empty string".to_string());
+    }
+    stack.push(Value::String(string.replace(&pattern, &replacement)));
+    Ok(())
+}
+
+/**
+ * This function changes a string with a function that takes the string and gives a new one.
+ * It is used by the words that change case and trim.
+ */
+pub fn map_string(stack: &mut Stack, word: &str, change: fn(&str) -> String) -> Result<(), String> {
+    let string = pop_string(stack, word)?;
+    stack.push(Value::String(change(&string)));
+    Ok(())
+}
+
+/**
+ * This function compares a string with another string with a function, and pushes the result.
+ * It is used by contains, startsWith and endsWith.
+ */
+pub fn test_string(stack: &mut Stack, word: &str, test: fn(&str, &str) -> bool) -> Result<(), String> {
+    let other = pop_string(stack, word)?;
+    let string = pop_string(stack, word)?;
+    stack.push(Value::Boolean(test(&string, &other)));
+    Ok(())
+}
+
+/**
+ * This function finds the first index of a part in a string, or of an element in a list.
+ * It pushes -1 if it is not found.
+ */
+pub fn index_of(stack: &mut Stack) -> Result<(), String> {
+    let needle = stack.pop()?;
+    let index = match (stack.pop()?, needle) {
+        (Value::String(string), Value::String(part)) => {
+            string.find(&part).map(|byte| string[..byte].chars().count())
+        },
+        (Value::List(list), element) => list.iter().position(|value| *value == element),
+        _ => return Err("Invalid operation, indexOf expects a string and a string, or a list and an element".to_string()),
+    };
+    stack.push(Value::Int(index.map_or(-1, |index| index as i64)));
+    Ok(())
+}
+
+/**
+ * This function makes a string that is a string written a number of times after each other.
+ */
+pub fn repeat_string(stack: &mut Stack) -> Result<(), String> {
+    let count = pop_index(stack, "repeatString")?;
+    let string = pop_string(stack, "repeatString")?;
+    stack.push(Value::String(string.repeat(count)));
+    Ok(())
+}
+
+/**
+ * This function makes a string at least width characters long, by adding a fill character to the left or the right.
+ * A string that is already long enough is left as it is.
+ * An empty fill pads with spaces, since a string literal can not hold only a space.
+ * If the fill is longer than one character, it will return an error.
+ */
+pub fn pad(stack: &mut Stack, word: &str, left: bool) -> Result<(), String> {
+    let fill = pop_string(stack, word)?;
+    let width = pop_index(stack, word)?;
+    let string = pop_string(stack, word)?;
+    let mut fill_chars = fill.chars();
+    let fill = match (fill_chars.next(), fill_chars.next()) {
+        (None, _) => ' ',
+        (Some(fill), None) => fill,
+        _ => return Err(format!("Invalid operation, {} expects a single character to fill with, got \" {} \"", word, fill)),
+    };
+    let padding = fill.to_string().repeat(width.saturating_sub(string.chars().count()));
+    if left {
+        stack.push(Value::String(padding + &string));
+    } else {
+        stack.push(Value::String(string + &padding));
+    }
+    Ok(())
+}
+
+/**
+ * This function reverses the characters of a string or the elements of a list.
+ */
+pub fn reverse(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::String(string) => stack.push(Value::String(string.chars().rev().collect())),
+        Value::List(mut list) => {
+            list.reverse();
+            stack.push(Value::List(list));
+        },
+        _ => return Err("Invalid operation, reverse expects a string or a list".to_string()),
+    }
+    Ok(())
+}
@@ -0,0 +1,305 @@
+/*!
+ * This module contains functions for the time and the clock.
+ * Times are Unix timestamps, the number of seconds since 1970-01-01 00:00:00 UTC, as integers.
+ *
+ * formatTime and parseTime use a format where %Y is the year, %m the month, %d the day, %H the hour, %M the minute,
+ * %S the second, %j the day of the year, %a and %b the short English names of the weekday and month,
+ * %z the offset from UTC like +02:00 and %% a percent sign. Everything else in the format is written as it is.
+ * The dates are converted by hand with the proleptic Gregorian calendar, so no time zone database is needed.
+ */
+
+use std::{sync::OnceLock, thread, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+use crate::{stack::Stack, types::Value};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// the clock word measures from the first time the clock is used
+static START: OnceLock<Instant> = OnceLock::new();
+
+/**
+ * This function pushes the current time as a Unix timestamp in seconds.
+ */
+pub fn now(stack: &mut Stack) -> Result<(), String> {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?;
+    stack.push(Value::Int(since_epoch.as_secs() as i64));
+    Ok(())
+}
+
+/**
+ * This function pushes the current time as a Unix timestamp in milliseconds.
+ */
+pub fn now_millis(stack: &mut Stack) -> Result<(), String> {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?;
+    stack.push(Value::Int(since_epoch.as_millis() as i64));
+    Ok(())
+}
+
+/**
+ * This function pushes the milliseconds since the clock was first used, as a float.
+ * The clock never goes backwards, even if the time of the computer is changed, so it is used to measure how long things take.
+ */
+pub fn clock(stack: &mut Stack) -> Result<(), String> {
+    let start = START.get_or_init(Instant::now);
+    stack.push(Value::Float(start.elapsed().as_secs_f64() * 1000.0));
+    Ok(())
+}
+
+/**
+ * This function waits for a number of milliseconds.
+ * If the number is negative, it will return an error.
+ */
+pub fn sleep(stack: &mut Stack) -> Result<(), String> {
+    match stack.pop()? {
+        Value::Int(millis) if millis >= 0 => {
+            thread::sleep(Duration::from_millis(millis as u64));
+            Ok(())
+        },
+        _ => Err("Invalid operation, sleep expects a number of milliseconds that is zero or more".to_string()),
+    }
+}
+
+/**
+ * This function turns a number of days since 1970-01-01 into a year, month and day.
+ * It is the days_from_civil algorithm by Howard Hinnant, the other way around.
+ */
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/**
+ * This function turns a year, month and day into the number of days since 1970-01-01.
+ */
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/**
+ * This function writes an offset from UTC in minutes like +02:00.
+ */
+fn write_offset(offset: i64) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    format!("{}{:02}:{:02}", sign, offset.abs() / 60, offset.abs() % 60)
+}
+
+/**
+ * This function writes a timestamp with a format, at an offset from UTC in minutes.
+ */
+fn write_time(timestamp: i64, offset: i64, format: &str) -> Result<String, String> {
+    let local = timestamp.checked_add(offset * 60).ok_or("Invalid operation, the time is out of range")?;
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let seconds = local.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let mut result = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => result.push_str(&format!("{:04}", year)),
+            Some('m') => result.push_str(&format!("{:02}", month)),
+            Some('d') => result.push_str(&format!("{:02}", day)),
+            Some('H') => result.push_str(&format!("{:02}", seconds / 3600)),
+            Some('M') => result.push_str(&format!("{:02}", seconds / 60 % 60)),
+            Some('S') => result.push_str(&format!("{:02}", seconds % 60)),
+            Some('j') => result.push_str(&format!("{:03}", days - days_from_civil(year, 1, 1) + 1)),
+            Some('a') => result.push_str(WEEKDAYS[(days + 3).rem_euclid(7) as usize]),     // 1970-01-01 was a thursday
+            Some('b') => result.push_str(MONTHS[month as usize - 1]),
+            Some('z') => result.push_str(&write_offset(offset)),
+            Some('%') => result.push('%'),
+            Some(other) => return Err(format!("Invalid time format, unknown specifier %{}", other)),
+            None => return Err("Invalid time format, it ends with %".to_string()),
+        }
+    }
+    Ok(result)
+}
+
+/**
+ * This function pops the format, and the timestamp below it, for formatTime and formatTimeAt.
+ */
+fn pop_time(stack: &mut Stack, word: &str) -> Result<(String, i64), String> {
+    let format = stack.pop()?;
+    let timestamp = stack.pop()?;
+    match (timestamp, format) {
+        (Value::Int(timestamp), Value::String(format)) => Ok((format, timestamp)),
+        _ => Err(format!("Invalid operation, {} expects a timestamp and a format", word)),
+    }
+}
+
+/**
+ * This function writes a timestamp as a string in UTC, timestamp format formatTime.
+ */
+pub fn format_time(stack: &mut Stack) -> Result<(), String> {
+    let (format, timestamp) = pop_time(stack, "formatTime")?;
+    stack.push(Value::String(write_time(timestamp, 0, &format)?));
+    Ok(())
+}
+
+/**
+ * This function writes a timestamp as a string at a fixed offset from UTC in minutes, timestamp offset format formatTimeAt.
+ * If the offset is more than a day, it will return an error.
+ */
+pub fn format_time_at(stack: &mut Stack) -> Result<(), String> {
+    let format = stack.pop()?;
+    let offset = match stack.pop()? {
+        Value::Int(offset) if offset.abs() < 24 * 60 => offset,
+        _ => return Err("Invalid operation, formatTimeAt expects an offset in minutes that is less than a day".to_string()),
+    };
+    stack.push(format);
+    let (format, timestamp) = pop_time(stack, "formatTimeAt")?;
+    stack.push(Value::String(write_time(timestamp, offset, &format)?));
+    Ok(())
+}
+
+/**
+ * This function reads a number of at most max digits, with a sign in front if signed is true.
+ */
+fn read_number(text: &[char], position: &mut usize, max: usize, signed: bool) -> Option<i64> {
+    let start = *position;
+    if signed && matches!(text.get(*position), Some('-' | '+')) {
+        *position += 1;
+    }
+    let digits_start = *position;
+    while *position < text.len() && *position - digits_start < max && text[*position].is_ascii_digit() {
+        *position += 1;
+    }
+    if *position == digits_start {
+        return None;
+    }
+    text[start..*position].iter().collect::<String>().parse().ok()
+}
+
+/**
+ * This function reads one of the names in a list, and returns its index.
+ */
+fn read_name(text: &[char], position: &mut usize, names: &[&str]) -> Option<usize> {
+    let index = names.iter().position(|name| {
+        let length = name.chars().count();
+        text.len() >= *position + length && text[*position..*position + length].iter().collect::<String>().eq_ignore_ascii_case(name)
+    })?;
+    *position += names[index].chars().count();
+    Some(index)
+}
+
+/**
+ * This function reads an offset from UTC like +02:00, +0200 or Z, and returns it in minutes.
+ */
+fn read_offset(text: &[char], position: &mut usize) -> Option<i64> {
+    if text.get(*position) == Some(&'Z') {
+        *position += 1;
+        return Some(0);
+    }
+    let sign = match text.get(*position) {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return None,
+    };
+    *position += 1;
+    let hours = read_number(text, position, 2, false)?;
+    if text.get(*position) == Some(&':') {
+        *position += 1;
+    }
+    let minutes = read_number(text, position, 2, false)?;
+    Some(sign * (hours * 60 + minutes))
+}
+
+/**
+ * This function reads a time from a string with a format, and returns the timestamp.
+ * Parts that are not in the format are 1970-01-01 00:00:00, and without %z the time is in UTC.
+ */
+fn read_time(input: &str, format: &str) -> Option<i64> {
+    let text = input.chars().collect::<Vec<char>>();
+    let mut position = 0;
+    let (mut year, mut month, mut day, mut hour, mut minute, mut second, mut offset) = (1970, 1, 1, 0, 0, 0, 0);
+    let mut chars = format.chars();
+    let mut by_day_of_year = false;     // true if the day was read with %j, so it can be up to 366
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if text.get(position) != Some(&c) {
+                return None;
+            }
+            position += 1;
+            continue;
+        }
+        match chars.next()? {
+            'Y' => year = read_number(&text, &mut position, 9, true)?,
+            'm' => month = read_number(&text, &mut position, 2, false)?,
+            'd' => day = read_number(&text, &mut position, 2, false)?,
+            'H' => hour = read_number(&text, &mut position, 2, false)?,
+            'M' => minute = read_number(&text, &mut position, 2, false)?,
+            'S' => second = read_number(&text, &mut position, 2, false)?,
+            'j' => {
+                let day_of_year = read_number(&text, &mut position, 3, false)?;
+                month = 1;
+                day = day_of_year;
+                by_day_of_year = true;
+            },
+            'a' => {
+                read_name(&text, &mut position, &WEEKDAYS)?;
+            },
+            'b' => month = read_name(&text, &mut position, &MONTHS)? as i64 + 1,
+            'z' => offset = read_offset(&text, &mut position)?,
+            '%' => {
+                if text.get(position) != Some(&'%') {
+                    return None;
+                }
+                position += 1;
+            },
+            _ => return None,
+        }
+    }
+    let day_limit = if by_day_of_year { if is_leap_year(year) { 366 } else { 365 } } else { days_in_month(year, month) };
+    if position != text.len() || !(1..=12).contains(&month) || day < 1 || day > day_limit || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let days = days_from_civil(year, month, 1) + day - 1;
+    Some(days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second - offset * 60)
+}
+
+/**
+ * This function reads a time from a string with a format and pushes the timestamp, string format parseTime.
+ * If the string does not match the format or is not a valid date, it will return an error.
+ */
+pub fn parse_time(stack: &mut Stack) -> Result<(), String> {
+    let format = stack.pop()?;
+    let input = stack.pop()?;
+    match (input, format) {
+        (Value::String(input), Value::String(format)) => {
+            let timestamp = read_time(&input, &format).ok_or(format!("Invalid time \" {} \" for the format \" {} \"", input, format))?;
+            stack.push(Value::Int(timestamp));
+            Ok(())
+        },
+        _ => Err("Invalid operation, parseTime expects a string and a format".to_string()),
+    }
+}
@@ -0,0 +1,151 @@
+use std::io;
+
+use crate::{functions, generator::Generator, operations::flow, signal::Signal, stack::Stack, types::Value, variables};
+
+/**
+ * A Sequence is a lazy list, its elements are only made when they are needed.
+ * This makes it possible to work with sequences that never end, like all numbers from 1 or the lines from the input.
+ *
+ * A Sequence is a value like any other, so it is never changed when it is used.
+ * To go through it, it is cloned and the clone keeps track of how far it has come.
+ */
+#[derive(Debug, Clone)]
+pub enum Sequence {
+    Range { next: Option<i64>, end: Option<i64>, step: i64 },      // the numbers from next to end, or forever if there is no end
+    Iterate { next: Box<Value>, block: Box<Value>, started: bool },     // next, then the block run on it, and so on
+    Repeat(Box<Value>),                 // the same value forever
+    Generate(Box<Value>),               // the values pushed by running the block, until it breaks
+    Lines,                              // the lines read from the input, until it ends
+    List(Vec<Value>, usize),            // the elements of a list, from the index
+    Generator(Generator),               // the values yielded by a generator, it is shared and not started over
+    Map(Box<Sequence>, Box<Value>),
+    Filter(Box<Sequence>, Box<Value>),
+    Take(Box<Sequence>, usize),
+    Drop(Box<Sequence>, usize),
+}
+
+impl Sequence {
+    /**
+     * This function makes the next element of the sequence.
+     * It returns None when the sequence has ended.
+     * Blocks in the sequence are run on the stack, so it needs the same arguments as exec.
+     */
+    pub fn next(&mut self, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<Option<Value>, Signal> {
+        match self {
+            Sequence::Range { next, end, step } => {
+                let current = match *next {
+                    Some(current) => current,
+                    None => return Ok(None),
+                };
+                let ended = match end {
+                    Some(end) => (*step > 0 && current > *end) || (*step < 0 && current < *end),
+                    None => false,
+                };
+                if ended {
+                    return Ok(None);
+                }
+                *next = current.checked_add(*step);     // None if the next number does not fit, then this is the last one
+                Ok(Some(Value::Int(current)))
+            },
+            Sequence::Iterate { next, block, started } => {
+                if *started {
+                    stack.push((**next).clone());
+                    block.exec(stack, variables, functions)?;
+                    **next = stack.pop()?;
+                }
+                *started = true;
+                Ok(Some((**next).clone()))
+            },
+            Sequence::Repeat(value) => Ok(Some((**value).clone())),
+            Sequence::Generate(block) => {
+                if flow::run_body(block, stack, variables, functions)? {
+                    Ok(Some(stack.pop()?))
+                } else {
+                    Ok(None)
+                }
+            },
+            Sequence::Lines => {
+                let mut line = String::new();
+                match io::stdin().read_line(&mut line) {
+                    Ok(0) => Ok(None),
+                    Ok(_) => Ok(Some(Value::String(line.trim_end_matches(['\n', '\r']).to_string()))),
+                    Err(e) => Err(Signal::Error(e.to_string())),
+                }
+            },
+            Sequence::List(list, index) => {
+                let value = list.get(*index).cloned();
+                *index += 1;
+                Ok(value)
+            },
+            Sequence::Generator(generator) => generator.resume(),
+            Sequence::Map(sequence, block) => {
+                match sequence.next(stack, variables, functions)? {
+                    Some(value) => {
+                        let depth = stack.len();
+                        stack.push(value);
+                        if flow::run_body(block, stack, variables, functions)? {
+                            return Ok(Some(stack.pop()?));
+                        }
+                        stack.truncate(depth);
+                        *self = Sequence::List(Vec::new(), 0);      // a break ends the sequence, also for the next elements
+                        Ok(None)
+                    },
+                    None => Ok(None),
+                }
+            },
+            Sequence::Filter(sequence, block) => {
+                while let Some(value) = sequence.next(stack, variables, functions)? {
+                    let depth = stack.len();
+                    stack.push(value.clone());
+                    match block.exec(stack, variables, functions) {
+                        Ok(()) => {},
+                        Err(Signal::Continue) => {      // the element is left out
+                            stack.truncate(depth);
+                            continue;
+                        },
+                        Err(Signal::Break) => {
+                            stack.truncate(depth);
+                            *self = Sequence::List(Vec::new(), 0);
+                            return Ok(None);
+                        },
+                        Err(signal) => return Err(signal),
+                    }
+                    match stack.pop()? {
+                        Value::Boolean(true) => return Ok(Some(value)),
+                        Value::Boolean(false) => {},
+                        _ => return Err("Invalid operation, the block of filter has to push a boolean".into()),
+                    }
+                }
+                Ok(None)
+            },
+            Sequence::Take(sequence, remaining) => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+                *remaining -= 1;
+                sequence.next(stack, variables, functions)
+            },
+            Sequence::Drop(sequence, skip) => {
+                while *skip > 0 {
+                    *skip -= 1;
+                    if sequence.next(stack, variables, functions)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+                sequence.next(stack, variables, functions)
+            },
+        }
+    }
+
+    /**
+     * This function makes all the elements of the sequence and returns them as a list.
+     * It never returns for a sequence that does not end.
+     */
+    pub fn collect(mut self, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<Vec<Value>, Signal> {
+        let mut list = Vec::new();
+        while let Some(value) = self.next(stack, variables, functions)? {
+            list.push(value);
+        }
+        Ok(list)
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+use crate::types::Value;
+
+/**
+ * A Signal is what stops a block from running to the end.
+ * It travels up through the blocks that are running until something handles it,
+ * if nothing does it ends the program in file mode, or is printed in REPL mode.
+ *
+ * Error is an error from the interpreter, like a division by zero or a value of the wrong type.
+ * Throw is a value thrown by the program with throw.
+ * Break, Continue and Return are not errors, they are stopped by the loop or function they belong to.
+ */
+#[derive(Debug, Clone)]
+pub enum Signal {
+    Error(String),
+    Throw(Value),
+    Break,
+    Continue,
+    Return,
+}
+
+// Errors from the operations are strings, this lets them be passed on with the ? operator
+impl From<String> for Signal {
+    fn from(value: String) -> Self {
+        Signal::Error(value)
+    }
+}
+
+impl From<&str> for Signal {
+    fn from(value: &str) -> Self {
+        Signal::Error(value.to_string())
+    }
+}
+
+// Implement Display trait for Signal to print it when nothing handles it
+impl Display for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Signal::Error(message) => write!(f, "{}", message),
+            Signal::Throw(value) => write!(f, "Uncaught exception: {}", value),
+            Signal::Break => write!(f, "break outside of a loop"),
+            Signal::Continue => write!(f, "continue outside of a loop"),
+            Signal::Return => write!(f, "return outside of a function"),
+        }
+    }
+}
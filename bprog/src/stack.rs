@@ -1,27 +1,146 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use crate::types::Value;
 
+/**
+ * The default number of nested block executions allowed before the interpreter gives up.
+ * The interpreter thread gets enough native stack for the maximum depth, see native_stack_size.
+ */
+pub const DEFAULT_MAX_DEPTH: usize = 10000;
+
+/**
+ * The highest the maximum depth can be set to, the native stack for more would be bigger than most computers can give.
+ */
+pub const MAX_DEPTH_LIMIT: usize = 100000;
+
+// a nested block takes about 29 KB of native stack in a debug build and about 1.5 KB in a release build,
+// this leaves room to spare without reserving gigabytes for a release build
+const NATIVE_STACK_PER_DEPTH: usize = if cfg!(debug_assertions) { 40 * 1024 } else { 4 * 1024 };
+const NATIVE_STACK_BASE: usize = 8 * 1024 * 1024;
+
+/**
+ * This function returns how much native stack a thread needs to run blocks nested max_depth deep,
+ * so the depth limit is always reached before the native stack overflows.
+ */
+pub fn native_stack_size(max_depth: usize) -> usize {
+    NATIVE_STACK_BASE + max_depth * NATIVE_STACK_PER_DEPTH
+}
+
 /**
  * Since rust does not have a built-in stack data structure, we need to implement our own.
  * The Stack struct is a wrapper around a vector of Value.
+ * It also keeps track of how deep the interpreter currently is in nested block executions,
+ * so that runaway recursion ends in an error instead of overflowing the native stack.
+ * Next to the main stack there is a return stack, used to put values aside for a while with >r and r>,
+ * and any number of named stacks.
  */
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stack {
     stack: Vec<Value>,
+    depth: usize,
+    max_depth: usize,
+    aux: Vec<Value>,
+    named: HashMap<String, Vec<Value>>,
 }
 
 impl Stack { 
     pub fn new() -> Self {
         Stack {
             stack: Vec::new(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            aux: Vec::new(),
+            named: HashMap::new(),
+        }
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /**
+     * This function makes a new empty stack with the same maximum depth, it is used by generators.
+     */
+    pub fn fresh(&self) -> Self {
+        Stack {
+            stack: Vec::new(),
+            depth: 0,
+            max_depth: self.max_depth,
+            aux: Vec::new(),
+            named: HashMap::new(),
+        }
+    }
+
+    /**
+     * This function makes a new stack with a copy of only the top count values, it is used to check contracts.
+     * It keeps the depth, so blocks run on it still count towards the maximum depth.
+     * If there are fewer values than count, it will return an error.
+     */
+    pub fn copy_top(&self, count: usize) -> Result<Self, String> {
+        if count > self.stack.len() {
+            return Err("Stack is empty".to_string());
         }
+        Ok(Stack {
+            stack: self.stack[self.stack.len() - count..].to_vec(),
+            depth: self.depth,
+            max_depth: self.max_depth,
+            aux: Vec::new(),
+            named: HashMap::new(),
+        })
+    }
+
+    /**
+     * This function is called every time a block starts executing.
+     * If the maximum depth is already reached it will return an error instead.
+     */
+    pub fn enter(&mut self) -> Result<(), String> {
+        if self.depth >= self.max_depth {
+            return Err(format!("maximum recursion depth exceeded ({})", self.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /**
+     * This function is called every time a block is done executing, also when it failed.
+     */
+    pub fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
     }
 
     pub fn len(&self) -> usize {
         self.stack.len()
     }
 
+    /**
+     * This function removes values from the top of the stack until only len values are left.
+     * If there are len values or less on the stack, nothing happens.
+     */
+    pub fn truncate(&mut self, len: usize) {
+        self.stack.truncate(len);
+    }
+
+    /**
+     * This function returns a copy of the stack, the return stack and the named stacks, so they can be put back with restore.
+     */
+    pub fn snapshot(&self) -> Stack {
+        self.clone()
+    }
+
+    /**
+     * This function replaces the values on the stack, the return stack and the named stacks with the values from a snapshot.
+     * The depth is kept, since the blocks that are running are still running.
+     */
+    pub fn restore(&mut self, snapshot: Stack) {
+        self.stack = snapshot.stack;
+        self.aux = snapshot.aux;
+        self.named = snapshot.named;
+    }
+
     pub fn push(&mut self, value: Value) {
         self.stack.push(value);
     }
@@ -30,6 +149,14 @@ impl Stack {
         self.stack.pop().ok_or("Stack is empty".to_string())
     }
 
+    /**
+     * This function returns a value on the stack without removing it.
+     * The index is counted from the top, so 0 is the top of the stack.
+     */
+    pub fn peek(&self, index: usize) -> Option<&Value> {
+        self.stack.iter().rev().nth(index)
+    }
+
 
     /**
      * This function swaps the top two elements on the stack.
@@ -53,6 +180,234 @@ impl Stack {
         self.stack.push(top);
         Ok(())
     }
+
+    /**
+     * This function checks that there are at least count values on the stack,
+     * so the operations below fail without changing the stack.
+     */
+    fn require(&self, count: usize) -> Result<(), String> {
+        if self.stack.len() < count {
+            return Err(format!("Stack has too few values, {} are needed but {} are on the stack", count, self.stack.len()));
+        }
+        Ok(())
+    }
+
+    /**
+     * This function copies the second element to the top, a b -> a b a.
+     */
+    pub fn over(&mut self) -> Result<(), String> {
+        self.pick_at(1)
+    }
+
+    /**
+     * This function moves the third element to the top, a b c -> b c a.
+     */
+    pub fn rot(&mut self) -> Result<(), String> {
+        self.roll_at(2)
+    }
+
+    /**
+     * This function moves the top element down to the third place, a b c -> c a b.
+     */
+    pub fn rot_back(&mut self) -> Result<(), String> {
+        self.require(3)?;
+        let top = self.stack.pop().unwrap();
+        self.stack.insert(self.stack.len() - 2, top);
+        Ok(())
+    }
+
+    /**
+     * This function removes the second element, a b -> b.
+     */
+    pub fn nip(&mut self) -> Result<(), String> {
+        self.require(2)?;
+        self.stack.remove(self.stack.len() - 2);
+        Ok(())
+    }
+
+    /**
+     * This function copies the top element below the second, a b -> b a b.
+     */
+    pub fn tuck(&mut self) -> Result<(), String> {
+        self.require(2)?;
+        let top = self.stack[self.stack.len() - 1].clone();
+        self.stack.insert(self.stack.len() - 2, top);
+        Ok(())
+    }
+
+    /**
+     * This function duplicates the top two elements, a b -> a b a b.
+     */
+    pub fn dup2(&mut self) -> Result<(), String> {
+        self.require(2)?;
+        self.stack.extend_from_within(self.stack.len() - 2..);
+        Ok(())
+    }
+
+    /**
+     * This function removes the top two elements, a b ->.
+     */
+    pub fn drop2(&mut self) -> Result<(), String> {
+        self.require(2)?;
+        self.stack.truncate(self.stack.len() - 2);
+        Ok(())
+    }
+
+    /**
+     * This function copies the element at the index to the top, the index is counted from the top so 0 is the same as dup.
+     */
+    fn pick_at(&mut self, index: usize) -> Result<(), String> {
+        self.require(index + 1)?;
+        let value = self.stack[self.stack.len() - 1 - index].clone();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function moves the element at the index to the top, the index is counted from the top so 1 is the same as swap.
+     */
+    fn roll_at(&mut self, index: usize) -> Result<(), String> {
+        self.require(index + 1)?;
+        let value = self.stack.remove(self.stack.len() - 1 - index);
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function pops an index for pick and roll, if there are enough elements below it.
+     * If the value is not an integer that is zero or more, or the stack is too small, it will return an error
+     * and leave the stack as it was.
+     */
+    fn pop_index(&mut self, word: &str) -> Result<usize, String> {
+        let index = match self.stack.last() {
+            Some(Value::Int(index)) if *index >= 0 => *index as usize,
+            Some(_) => return Err(format!("Invalid operation, {} expects an index that is zero or more", word)),
+            None => return Err("Stack is empty".to_string()),
+        };
+        self.require(index + 2)?;       // the element at the index and the index itself
+        self.stack.pop();
+        Ok(index)
+    }
+
+    /**
+     * This function copies the element at the index on top of the stack to the top, n pick.
+     */
+    pub fn pick(&mut self) -> Result<(), String> {
+        let index = self.pop_index("pick")?;
+        self.pick_at(index)
+    }
+
+    /**
+     * This function moves the element at the index on top of the stack to the top, n roll.
+     */
+    pub fn roll(&mut self) -> Result<(), String> {
+        let index = self.pop_index("roll")?;
+        self.roll_at(index)
+    }
+
+    /**
+     * This function removes all the elements on the stack.
+     */
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /**
+     * This function moves the top element of the stack to the return stack.
+     */
+    pub fn push_aux(&mut self) -> Result<(), String> {
+        let value = self.pop()?;
+        self.aux.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function moves the top element of the return stack back to the stack.
+     * If the return stack is empty, it will return an error.
+     */
+    pub fn pop_aux(&mut self) -> Result<(), String> {
+        let value = self.aux.pop().ok_or("Return stack is empty")?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function copies the top element of the return stack to the stack.
+     * If the return stack is empty, it will return an error.
+     */
+    pub fn peek_aux(&mut self) -> Result<(), String> {
+        let value = self.aux.last().cloned().ok_or("Return stack is empty")?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    pub fn aux_len(&self) -> usize {
+        self.aux.len()
+    }
+
+    pub fn truncate_aux(&mut self, len: usize) {
+        self.aux.truncate(len);
+    }
+
+    /**
+     * This function moves the element below the name to a named stack, x 'name >stack, the named stack is made if it does not exist.
+     */
+    pub fn push_named(&mut self) -> Result<(), String> {
+        let name = self.peek_name(">stack")?;
+        self.require(2)?;
+        self.stack.pop();
+        let value = self.pop()?;
+        self.named.entry(name).or_default().push(value);
+        Ok(())
+    }
+
+    /**
+     * This function moves the top element of a named stack to the stack, 'name stack> -> x.
+     * If the named stack is empty, it will return an error.
+     */
+    pub fn pop_named(&mut self) -> Result<(), String> {
+        let name = self.peek_name("stack>")?;
+        let value = self.named.get_mut(&name).and_then(|named| named.pop()).ok_or(format!("Stack {} is empty", name))?;
+        self.stack.pop();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function copies the top element of a named stack to the stack, 'name stack@ -> x.
+     * If the named stack is empty, it will return an error.
+     */
+    pub fn peek_named(&mut self) -> Result<(), String> {
+        let name = self.peek_name("stack@")?;
+        let value = self.named.get(&name).and_then(|named| named.last()).cloned().ok_or(format!("Stack {} is empty", name))?;
+        self.stack.pop();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /**
+     * This function pushes the number of elements on a named stack, a stack that does not exist has none.
+     */
+    pub fn named_depth(&mut self) -> Result<(), String> {
+        let name = self.peek_name("stackDepth")?;
+        self.stack.pop();
+        let depth = self.named.get(&name).map_or(0, |named| named.len());
+        self.stack.push(Value::Int(depth as i64));
+        Ok(())
+    }
+
+    /**
+     * This function returns the name of a named stack on top of the stack, without popping it,
+     * so a word that fails leaves the stack as it was.
+     * If the value is not a symbol, it will return an error.
+     */
+    fn peek_name(&self, word: &str) -> Result<String, String> {
+        match self.stack.last() {
+            Some(Value::Symbol(name)) => Ok(name.clone()),
+            Some(_) => Err(format!("Invalid operation, {} expects the name of a stack", word)),
+            None => Err("Stack is empty".to_string()),
+        }
+    }
 }
 
 // Implement Display trait for Stack to print the stack
@@ -1,7 +1,7 @@
-use std::{fmt::Display, ops::{Add, Div, Mul, Not, Sub}};
+use std::{collections::BTreeMap, fmt::Display, ops::{Add, Div, Mul, Not, Sub}};
 
-use crate::{functions, operations::{self, arithmetic, io, logic}, variables::{self, Variables}};
-use crate::stack::Stack;
+use crate::{functions, operations::{self, arithmetic, io, logic}, sequence::Sequence, variables::{self, Variables}};
+use crate::{generator::Generator, signal::Signal, stack::Stack};
 
 /**
  * The Value enum represents the different types of values that can be used in the language.
@@ -10,6 +10,7 @@ use crate::stack::Stack;
  * 
  * Block is an executable code block, it is represented as a vector of strings.
  * It is lazily evaluated, and only used when needed
+ * Closure is a block that also carries the local bindings from where it was made.
  */
 
 #[derive(Debug, Clone)]
@@ -19,11 +20,34 @@ pub enum Value {
     String(String),
     Boolean(bool),
     List(Vec<Value>),
+    Map(BTreeMap<String, Value>),       // Map is a record of values by name, the names are kept sorted
     Block(Vec<String>),
+    Closure(Vec<String>, variables::Scope),     // Closure is a block together with the local bindings it captured
+    Seq(Sequence),          // Seq is a lazy list, its elements are made when they are needed
+    Generator(Generator),   // Generator is a block that is run a bit at a time, it stops every time it yields
     Symbol(String),         // Symbol is a type of Value that represents a variable or function name
 }
 
 impl Value {
+    /**
+     * This function returns the name of the type of the value.
+     * These are the names used in the signatures of typed functions.
+     */
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Float(_) => "Float",
+            Value::Int(_) => "Int",
+            Value::String(_) => "String",
+            Value::Boolean(_) => "Bool",
+            Value::List(_) => "List",
+            Value::Map(_) => "Map",
+            Value::Block(_) | Value::Closure(..) => "Block",
+            Value::Seq(_) => "Sequence",
+            Value::Generator(_) => "Generator",
+            Value::Symbol(_) => "Symbol",
+        }
+    }
+
     /**
      * This function takes a Value and a Variables object and returns a string representation of the Value.
      * It replaces any variables in the Value with their corresponding values in the Variables object.
@@ -60,7 +84,7 @@ impl Value {
     pub fn and(self, other: Self) -> Result<Value, String> {
         match (self, other) {
             (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a && b)),
-            _ => Err(format!("Invalid operation and")),
+            _ => Err("Invalid operation and".to_string()),
         }
     }
 
@@ -71,7 +95,7 @@ impl Value {
     pub fn or(self, other: Self) -> Result<Value, String> {
         match (self, other) {
             (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a || b)),
-            _ => Err(format!("Invalid operation or")),
+            _ => Err("Invalid operation or".to_string()),
         }
     }
 
@@ -105,9 +129,9 @@ impl Value {
         match value {
             Value::List(list) if !list.is_empty() => Ok(list[0].clone()),
             Value::String(string) if !string.is_empty() => Ok(Value::String(string.chars().nth(0).unwrap().to_string())),
-            Value::String(_) => Err(format!("Empty string")),
-            Value::List(_) => Err(format!("Empty list")),
-            _ =>  Err(format!("Invalid operation on head, expected list or string")),
+            Value::String(_) => Err("Empty string".to_string()),
+            Value::List(_) => Err("Empty list".to_string()),
+            _ =>  Err("Invalid operation on head, expected list or string".to_string()),
         }
     }
 
@@ -119,35 +143,36 @@ impl Value {
         match value {
             Value::List(list) if !list.is_empty() => Ok(Value::List(list[1..].to_vec())),
             Value::String(string) if !string.is_empty() => Ok(Value::String(string.chars().skip(1).collect())),
-            Value::String(_) => Err(format!("Empty string")),
-            Value::List(_) => Err(format!("Empty list")),
-            _ => Err(format!("Invalid operation on tail, expected list or string")),
+            Value::String(_) => Err("Empty string".to_string()),
+            Value::List(_) => Err("Empty list".to_string()),
+            _ => Err("Invalid operation on tail, expected list or string".to_string()),
         }
     }
 
     /**
      * This function returns true if the value is empty, false otherwise.
-     * If the value is not a list or string, it will return an error.
+     * If the value is not a list, string or map, it will return an error.
      */
     pub fn empty(value: &Value) -> Result<Value, String> {
         match value {
             Value::List(list ) => Ok(Value::Boolean(list.is_empty())),
             Value::String(string) => Ok(Value::Boolean(string.is_empty())),
-            _ => Err(format!("Invalid operation, expected list or string")),
+            Value::Map(map) => Ok(Value::Boolean(map.is_empty())),
+            _ => Err("Invalid operation, expected list, string or map".to_string()),
         }
     }
 
     /**
-     * This function returns the length of a list or string.
-     * If the value is not a list or string, it will return an error.
+     * This function returns the length of a list, string or map.
+     * If the value is not a list, string or map, it will return an error.
      */
     pub fn lenght(value: &Value) -> Result<Value, String> {
-        let result = match value {
+        match value {
             Value::List(list) => Ok(Value::Int(list.len() as i64)),
             Value::String(string) => Ok(Value::Int(string.chars().count() as i64)),
-            _ => Err(format!("Invalid operation, expected list or string")),
-        };
-        result
+            Value::Map(map) => Ok(Value::Int(map.len() as i64)),
+            _ => Err("Invalid operation, expected list, string or map".to_string()),
+        }
     }
 
     /**
@@ -183,174 +208,743 @@ impl Value {
                 stack.push(Value::List(words));
                 Ok(())
             },
-            _ => Err(format!("Invalid operation")),
+            _ => Err("Invalid operation".to_string()),
         }
     }
 
 
-    // This function will exeute a code block
-    pub fn exec(&self, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
+    // This function will exeute a code block, keeping track of how deeply blocks are nested
+    pub fn exec(&self, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+        stack.enter()?;
+        let result = self.run(stack, variables, functions);
+        stack.leave();      // leave even on error so the REPL can continue with a correct depth
+        result
+    }
+
+    // This function runs a block or a closure, it should only be called through exec
+    fn run(&self, stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
         match self {        // pattern matching on self
-            Value::Block(b) => {
-                let mut tokens = b.iter();
-                while let Some(token) = tokens.next() {
-                    if variables.get(&token.to_string()).is_some() {        // If the token is in variables
-                        stack.push(variables.get(&token.to_string()).unwrap().clone());        // push the value
-                        continue;        // and continue
-                    }
-                    if functions.get(&token.to_string()).is_some() {        // If the token is in functions
-                        let block = functions.get(&token.to_string()).unwrap().clone();        // run the function
-                        block.exec(stack, variables, functions)?;        // and continue
-                        continue;
-                    }
-                    match token.as_str() {
-                        "+" => {
-                            arithmetic::add(stack)?;
-                        },
-                        "-" => {
-                            arithmetic::sub(stack)?;
-                        },
-                        "*" => {
-                            arithmetic::mul(stack)?;
-                        },
-                        "/" => {
-                            arithmetic::div(stack)?;
-                        },
-                        "div" => {
-                            arithmetic::int_div(stack)?;
-                        },
-                        "<" => {
-                            arithmetic::lt(stack)?;
-                        },
-                        ">" => {
-                            arithmetic::gt(stack)?;
-                        },
-                        "==" => {
-                            arithmetic::eq(stack)?;
-                        },
-                        "dup" => {
-                            stack.dup()?;
-                        },
-                        "swap" => {
-                            stack.swap()?;
-                        },
-                        "pop" => {
-                            stack.pop()?;
-                        },
-                        "words" => {
-                            Self::words(stack)?;
-                        },
-                        "print" => {
-                            io::print(stack, variables)?;
-                        },
-                        "read" => {
-                            io::read(stack)?;
-                        },
-                        "parseInteger" => {
-                            let input = stack.pop()?;
-                            Self::parse_integer(&input.to_string())?;
-                            stack.push(input);
-                        },
-                        "parseFloat" => {
-                            let input = stack.pop()?;
-                            Self::parse_float(&input.to_string())?;
-                            stack.push(input);
-                        },
-                        "&&" => {
-                            logic::and(stack)?;
-                        },
-                        "||" => {
-                            logic::or(stack)?;
-                        }
-                        "not" => {
-                            logic::not(stack)?;
-                        },
-                        "[" => {
-                            let input = operations::flow::read_list(&mut tokens)?;
-                            stack.push(input);
-                        },
-                        "{" => {
-                            let input = operations::flow::read_block(&mut tokens)?;
-                            stack.push(input);
-                        },
-                        "\"" => {
-                            let input = operations::flow::read_string(&mut tokens);
-                            stack.push(input);
-                        },
-                        "head" => {
-                            operations::lists::head(stack)?;
-                        },
-                        "tail" => {
-                            operations::lists::tail(stack)?;
-                        },
-                        "empty" => {
-                            operations::lists::empty(stack)?;
-                        },
-                        "length" => {                            
-                            operations::lists::lenght(stack)?;
-                        },
-                        "cons" => {
-                            operations::lists::cons(stack)?;
-                        },
-                        "append" => {
-                            operations::lists::append(stack)?;
-                        },
-                        "if" => {
-                            operations::flow::if_block(&mut tokens, stack, variables, functions)?;
-                        },
-                        "println" => {
-                            io::println(stack, variables)?;
-                        },
-                        "each" => {
-                            operations::lists::each(&mut tokens, stack, variables, functions)?;
-                        },
-                        "map" => {
-                            operations::lists::map(&mut tokens, stack, variables, functions)?;
-                        },
-                        "foldl" => {
-                            operations::lists::foldl(&mut tokens, stack, variables, functions)?;
-                        },
-                        "loop" => {
-                            operations::flow::r#loop(&mut tokens, stack, variables, functions)?;
-                        },
-                        "times" => {
-                            operations::flow::times(&mut tokens, stack, variables, functions)?;
-                        },
-                        ":=" => {
-                            operations::flow::assign(stack, variables)?;
-                        },
-                        "fun" => {
-                            operations::flow::new_function(stack, functions)?;
-                        }
-                        "exec" => {
-                            operations::flow::exec(stack, variables, functions)?;
-                        }
-                        _ => {
-                            stack.push(convert(token));
-                        }
-                    }
+            Value::Block(b) => Self::run_tokens(b, stack, variables, functions),
+            Value::Closure(b, scope) => {
+                variables.push_scope(scope.clone());        // run the closure with the bindings it captured
+                let result = Self::run_tokens(b, stack, variables, functions);
+                variables.pop_scope();
+                result
+            },
+            _ => Err("Invalid operation, expected block".into()),             // only block can be executed
+        }
+    }
+
+    // This function runs the tokens of a block one by one
+    fn run_tokens(b: &[String], stack: &mut Stack, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+        let mut tokens = b.iter().peekable();        // peekable so words can check for optional parts after them
+        while let Some(token) = tokens.next() {
+            if let Some(name) = token.strip_prefix('\'').filter(|name| !name.is_empty()) {   // A quoted name is pushed as a symbol
+                stack.push(Value::Symbol(name.to_string()));        // without looking it up
+                continue;
+            }
+            if let Some(value) = variables.get(token) {        // If the token is in variables
+                stack.push(value);        // push the value
+                continue;        // and continue
+            }
+            if let Some(definition) = functions.get_macro(token).cloned() {        // If the token is a macro
+                operations::flow::call_macro(&mut tokens, &definition, stack, variables, functions)?;        // give it the code after it
+                continue;
+            }
+            if let Some(implementation) = functions.resolve(token, stack)? {       // If the token is in functions
+                operations::flow::call_function(token, &implementation, stack, variables, functions)?;        // run the implementation matching the stack
+                continue;        // and continue
+            }
+            match token.as_str() {
+                "+" => {
+                    arithmetic::add(stack)?;
+                },
+                "-" => {
+                    arithmetic::sub(stack)?;
+                },
+                "*" => {
+                    arithmetic::mul(stack)?;
+                },
+                "/" => {
+                    arithmetic::div(stack)?;
+                },
+                "div" => {
+                    arithmetic::int_div(stack)?;
+                },
+                "<" => {
+                    arithmetic::lt(stack)?;
+                },
+                ">" => {
+                    arithmetic::gt(stack)?;
+                },
+                "==" => {
+                    arithmetic::eq(stack)?;
+                },
+                "<=" => {
+                    arithmetic::le(stack)?;
+                },
+                ">=" => {
+                    arithmetic::ge(stack)?;
+                },
+                "!=" => {
+                    arithmetic::ne(stack)?;
+                },
+                "mod" => {
+                    operations::math::modulo(stack)?;
+                },
+                "rem" | "%" => {
+                    operations::math::remainder(stack)?;
+                },
+                "pow" => {
+                    operations::math::pow(stack)?;
+                },
+                "sqrt" => {
+                    operations::math::float_function(stack, "sqrt", f64::sqrt)?;
+                },
+                "exp" => {
+                    operations::math::float_function(stack, "exp", f64::exp)?;
+                },
+                "log" => {
+                    operations::math::float_function(stack, "log", f64::ln)?;
+                },
+                "log10" => {
+                    operations::math::float_function(stack, "log10", f64::log10)?;
+                },
+                "sin" => {
+                    operations::math::float_function(stack, "sin", f64::sin)?;
+                },
+                "cos" => {
+                    operations::math::float_function(stack, "cos", f64::cos)?;
+                },
+                "tan" => {
+                    operations::math::float_function(stack, "tan", f64::tan)?;
+                },
+                "asin" => {
+                    operations::math::float_function(stack, "asin", f64::asin)?;
+                },
+                "acos" => {
+                    operations::math::float_function(stack, "acos", f64::acos)?;
+                },
+                "atan" => {
+                    operations::math::float_function(stack, "atan", f64::atan)?;
+                },
+                "atan2" => {
+                    operations::math::atan2(stack)?;
+                },
+                "PI" => {
+                    stack.push(Value::Float(std::f64::consts::PI));
+                },
+                "E" => {
+                    stack.push(Value::Float(std::f64::consts::E));
+                },
+                "abs" => {
+                    operations::math::abs(stack)?;
+                },
+                "min" => {
+                    operations::math::min_max(stack, "min", false)?;
+                },
+                "max" => {
+                    operations::math::min_max(stack, "max", true)?;
+                },
+                "floor" => {
+                    operations::math::to_integer(stack, "floor", f64::floor)?;
+                },
+                "ceil" => {
+                    operations::math::to_integer(stack, "ceil", f64::ceil)?;
+                },
+                "round" => {
+                    operations::math::to_integer(stack, "round", f64::round)?;
+                },
+                "seed" => {
+                    operations::random::seed(stack)?;
+                },
+                "randomInt" => {
+                    operations::random::random_int(stack)?;
+                },
+                "randomFloat" => {
+                    operations::random::random_float(stack)?;
+                },
+                "choice" => {
+                    operations::random::choice(stack)?;
+                },
+                "shuffle" => {
+                    operations::random::shuffle(stack)?;
+                },
+                "sample" => {
+                    operations::random::sample(stack)?;
+                },
+                "now" => {
+                    operations::time::now(stack)?;
+                },
+                "nowMillis" => {
+                    operations::time::now_millis(stack)?;
+                },
+                "clock" => {
+                    operations::time::clock(stack)?;
+                },
+                "sleep" => {
+                    operations::time::sleep(stack)?;
+                },
+                "formatTime" => {
+                    operations::time::format_time(stack)?;
+                },
+                "formatTimeAt" => {
+                    operations::time::format_time_at(stack)?;
+                },
+                "parseTime" => {
+                    operations::time::parse_time(stack)?;
+                },
+                "parseJson" => {
+                    operations::json::parse_json(stack)?;
+                },
+                "toJson" => {
+                    operations::json::to_json(stack, false)?;
+                },
+                "toJsonPretty" => {
+                    operations::json::to_json(stack, true)?;
+                },
+                "parseCsv" => {
+                    operations::csv::parse_csv(stack)?;
+                },
+                "parseCsvWith" => {
+                    operations::csv::parse_csv_with(stack)?;
+                },
+                "toCsv" => {
+                    operations::csv::to_csv(stack)?;
+                },
+                "toCsvWith" => {
+                    operations::csv::to_csv_with(stack)?;
+                },
+                "gcd" => {
+                    operations::math::gcd(stack)?;
+                },
+                "lcm" => {
+                    operations::math::lcm(stack)?;
+                },
+                "bitAnd" => {
+                    operations::math::bitwise(stack, "bitAnd", |a, b| a & b)?;
+                },
+                "bitOr" => {
+                    operations::math::bitwise(stack, "bitOr", |a, b| a | b)?;
+                },
+                "bitXor" => {
+                    operations::math::bitwise(stack, "bitXor", |a, b| a ^ b)?;
+                },
+                "bitNot" => {
+                    operations::math::bit_not(stack)?;
+                },
+                "shl" => {
+                    operations::math::shift(stack, "shl", true)?;
+                },
+                "shr" => {
+                    operations::math::shift(stack, "shr", false)?;
+                },
+                "dup" => {
+                    stack.dup()?;
+                },
+                "swap" => {
+                    stack.swap()?;
+                },
+                "pop" => {
+                    stack.pop()?;
+                },
+                "over" => {
+                    stack.over()?;
+                },
+                "rot" => {
+                    stack.rot()?;
+                },
+                "-rot" => {
+                    stack.rot_back()?;
+                },
+                "nip" => {
+                    stack.nip()?;
+                },
+                "tuck" => {
+                    stack.tuck()?;
+                },
+                "2dup" => {
+                    stack.dup2()?;
+                },
+                "2drop" => {
+                    stack.drop2()?;
+                },
+                "pick" => {
+                    stack.pick()?;
+                },
+                "roll" => {
+                    stack.roll()?;
+                },
+                "depth" => {
+                    stack.push(Value::Int(stack.len() as i64));
+                },
+                "clear" => {
+                    stack.clear();
+                },
+                ">r" => {
+                    stack.push_aux()?;
+                },
+                "r>" => {
+                    stack.pop_aux()?;
+                },
+                "r@" => {
+                    stack.peek_aux()?;
+                },
+                ">stack" => {
+                    stack.push_named()?;
+                },
+                "stack>" => {
+                    stack.pop_named()?;
+                },
+                "stack@" => {
+                    stack.peek_named()?;
+                },
+                "stackDepth" => {
+                    stack.named_depth()?;
+                },
+                "dip" => {
+                    operations::combinators::dip(stack, variables, functions)?;
+                },
+                "keep" => {
+                    operations::combinators::keep(stack, variables, functions)?;
+                },
+                "bi" => {
+                    operations::combinators::bi(stack, variables, functions)?;
+                },
+                "tri" => {
+                    operations::combinators::tri(stack, variables, functions)?;
+                },
+                "cleave" => {
+                    operations::combinators::cleave(stack, variables, functions)?;
+                },
+                "words" => {
+                    Self::words(stack)?;
+                },
+                "split" => {
+                    operations::strings::split(stack)?;
+                },
+                "join" => {
+                    operations::strings::join(stack)?;
+                },
+                "slice" => {
+                    operations::strings::slice(stack)?;
+                },
+                "replace" => {
+                    operations::strings::replace(stack)?;
+                },
+                "upper" => {
+                    operations::strings::map_string(stack, "upper", |s| s.to_uppercase())?;
+                },
+                "lower" => {
+                    operations::strings::map_string(stack, "lower", |s| s.to_lowercase())?;
+                },
+                "trim" => {
+                    operations::strings::map_string(stack, "trim", |s| s.trim().to_string())?;
+                },
+                "trimStart" => {
+                    operations::strings::map_string(stack, "trimStart", |s| s.trim_start().to_string())?;
+                },
+                "trimEnd" => {
+                    operations::strings::map_string(stack, "trimEnd", |s| s.trim_end().to_string())?;
+                },
+                "contains" => {
+                    operations::strings::test_string(stack, "contains", |s, part| s.contains(part))?;
+                },
+                "startsWith" => {
+                    operations::strings::test_string(stack, "startsWith", |s, part| s.starts_with(part))?;
+                },
+                "endsWith" => {
+                    operations::strings::test_string(stack, "endsWith", |s, part| s.ends_with(part))?;
+                },
+                "indexOf" => {
+                    operations::strings::index_of(stack)?;
+                },
+                "repeatString" => {
+                    operations::strings::repeat_string(stack)?;
+                },
+                "padLeft" => {
+                    operations::strings::pad(stack, "padLeft", true)?;
+                },
+                "padRight" => {
+                    operations::strings::pad(stack, "padRight", false)?;
+                },
+                "reverse" => {
+                    operations::strings::reverse(stack)?;
+                },
+                "reMatch" => {
+                    operations::regex::is_match(stack)?;
+                },
+                "reFind" => {
+                    operations::regex::find(stack)?;
+                },
+                "reFindAll" => {
+                    operations::regex::find_all(stack)?;
+                },
+                "reCaptures" => {
+                    operations::regex::captures(stack)?;
+                },
+                "reReplace" => {
+                    operations::regex::replace(stack, variables, functions)?;
+                },
+                "reSplit" => {
+                    operations::regex::split(stack)?;
+                },
+                "print" => {
+                    io::print(stack, variables)?;
+                },
+                "read" => {
+                    io::read(stack)?;
+                },
+                "readFile" => {
+                    io::read_file(stack)?;
+                },
+                "writeFile" => {
+                    io::write_file(stack)?;
+                },
+                "parseInteger" => {
+                    let input = stack.pop()?;
+                    Self::parse_integer(&input.to_string())?;
+                    stack.push(input);
+                },
+                "parseFloat" => {
+                    let input = stack.pop()?;
+                    Self::parse_float(&input.to_string())?;
+                    stack.push(input);
+                },
+                "&&" => {
+                    logic::and(stack)?;
+                },
+                "||" => {
+                    logic::or(stack)?;
+                }
+                "not" => {
+                    logic::not(stack)?;
+                },
+                "[" => {
+                    let input = operations::flow::read_list(&mut tokens)?;
+                    stack.push(input);
+                },
+                "{" => {
+                    let input = operations::flow::read_block(&mut tokens)?;
+                    stack.push(input);
+                },
+                "\"" => {
+                    let input = operations::flow::read_string(&mut tokens);
+                    stack.push(input);
+                },
+                "f\"" => {
+                    operations::format::interpolate(&mut tokens, stack, variables)?;
+                },
+                "format" => {
+                    operations::format::format(stack)?;
+                },
+                "toString" => {
+                    operations::format::to_string(stack)?;
+                },
+                "head" => {
+                    operations::lists::head(stack)?;
+                },
+                "tail" => {
+                    operations::lists::tail(stack)?;
+                },
+                "empty" => {
+                    operations::lists::empty(stack)?;
+                },
+                "length" => {                            
+                    operations::lists::lenght(stack)?;
+                },
+                "cons" => {
+                    operations::lists::cons(stack)?;
+                },
+                "append" => {
+                    operations::lists::append(stack)?;
+                },
+                "if" => {
+                    operations::flow::if_block(&mut tokens, stack, variables, functions)?;
+                },
+                "cond" => {
+                    operations::flow::cond(&mut tokens, stack, variables, functions)?;
+                },
+                "case" => {
+                    operations::flow::case(&mut tokens, stack, variables, functions)?;
+                },
+                "println" => {
+                    io::println(stack, variables)?;
+                },
+                "each" => {
+                    operations::lists::each(&mut tokens, stack, variables, functions, false)?;
+                }
+                "eachWith" => {
+                    operations::lists::each(&mut tokens, stack, variables, functions, true)?;
+                },
+                "map" => {
+                    operations::lists::map(&mut tokens, stack, variables, functions, false)?;
+                }
+                "mapWith" => {
+                    operations::lists::map(&mut tokens, stack, variables, functions, true)?;
+                },
+                "foldl" => {
+                    operations::lists::foldl(&mut tokens, stack, variables, functions, false)?;
+                }
+                "foldlWith" => {
+                    operations::lists::foldl(&mut tokens, stack, variables, functions, true)?;
+                },
+                "foldr" => {
+                    operations::lists::foldr(&mut tokens, stack, variables, functions, false)?;
+                }
+                "foldrWith" => {
+                    operations::lists::foldr(&mut tokens, stack, variables, functions, true)?;
+                },
+                "scan" => {
+                    operations::lists::scan(&mut tokens, stack, variables, functions, false)?;
+                }
+                "scanWith" => {
+                    operations::lists::scan(&mut tokens, stack, variables, functions, true)?;
+                },
+                "any" => {
+                    operations::lists::any(&mut tokens, stack, variables, functions, false)?;
+                }
+                "anyWith" => {
+                    operations::lists::any(&mut tokens, stack, variables, functions, true)?;
+                },
+                "all" => {
+                    operations::lists::all(&mut tokens, stack, variables, functions, false)?;
+                }
+                "allWith" => {
+                    operations::lists::all(&mut tokens, stack, variables, functions, true)?;
+                },
+                "find" => {
+                    operations::lists::find(&mut tokens, stack, variables, functions, false)?;
+                }
+                "findWith" => {
+                    operations::lists::find(&mut tokens, stack, variables, functions, true)?;
+                },
+                "groupBy" => {
+                    operations::lists::group_by(&mut tokens, stack, variables, functions, false)?;
+                }
+                "groupByWith" => {
+                    operations::lists::group_by(&mut tokens, stack, variables, functions, true)?;
+                },
+                "partition" => {
+                    operations::lists::partition(&mut tokens, stack, variables, functions, false)?;
+                }
+                "partitionWith" => {
+                    operations::lists::partition(&mut tokens, stack, variables, functions, true)?;
+                },
+                "sort" => {
+                    operations::sorting::sort(stack, false)?;
+                },
+                "sortDesc" => {
+                    operations::sorting::sort(stack, true)?;
+                },
+                "sortBy" => {
+                    operations::sorting::sort_by(&mut tokens, stack, variables, functions, false, false)?;
+                },
+                "sortByWith" => {
+                    operations::sorting::sort_by(&mut tokens, stack, variables, functions, false, true)?;
+                },
+                "sortByDesc" => {
+                    operations::sorting::sort_by(&mut tokens, stack, variables, functions, true, false)?;
+                },
+                "sortByDescWith" => {
+                    operations::sorting::sort_by(&mut tokens, stack, variables, functions, true, true)?;
+                },
+                "sortOn" => {
+                    operations::sorting::sort_on(&mut tokens, stack, variables, functions, false, false)?;
+                },
+                "sortOnWith" => {
+                    operations::sorting::sort_on(&mut tokens, stack, variables, functions, false, true)?;
+                },
+                "sortOnDesc" => {
+                    operations::sorting::sort_on(&mut tokens, stack, variables, functions, true, false)?;
+                },
+                "sortOnDescWith" => {
+                    operations::sorting::sort_on(&mut tokens, stack, variables, functions, true, true)?;
+                },
+                "nth" => {
+                    operations::lists::nth(stack)?;
+                },
+                "zip" => {
+                    operations::lists::zip(stack)?;
+                },
+                "unzip" => {
+                    operations::lists::unzip(stack)?;
+                },
+                "flatten" => {
+                    operations::lists::flatten(stack)?;
+                },
+                "unique" => {
+                    operations::lists::unique(stack)?;
+                },
+                "chunk" => {
+                    operations::lists::chunk(stack)?;
+                },
+                "windows" => {
+                    operations::lists::windows(stack)?;
+                },
+                "filter" => {
+                    operations::sequences::filter(&mut tokens, stack, variables, functions, false)?;
+                }
+                "filterWith" => {
+                    operations::sequences::filter(&mut tokens, stack, variables, functions, true)?;
+                },
+                "take" => {
+                    operations::sequences::take(stack)?;
+                },
+                "drop" => {
+                    operations::sequences::drop(stack)?;
+                },
+                "range" => {
+                    operations::sequences::range(stack)?;
+                },
+                "from" => {
+                    operations::sequences::from(stack)?;
+                },
+                "iterate" => {
+                    operations::sequences::iterate(&mut tokens, stack)?;
+                },
+                "repeat" => {
+                    operations::sequences::repeat(stack)?;
+                },
+                "generate" => {
+                    operations::sequences::generate(&mut tokens, stack)?;
+                },
+                "lines" => {
+                    operations::sequences::lines(stack)?;
+                },
+                "lazy" => {
+                    operations::sequences::lazy(stack)?;
+                },
+                "collect" => {
+                    operations::sequences::collect(stack, variables, functions)?;
+                },
+                "generator" => {
+                    operations::sequences::generator(stack, variables, functions)?;
+                },
+                "next" => {
+                    operations::sequences::next(stack)?;
+                },
+                "yield" => {
+                    operations::sequences::r#yield(stack)?;
+                },
+                "loop" => {
+                    operations::flow::r#loop(&mut tokens, stack, variables, functions)?;
+                },
+                "times" => {
+                    operations::flow::times(&mut tokens, stack, variables, functions)?;
+                },
+                "while" => {
+                    operations::flow::r#while(&mut tokens, stack, variables, functions)?;
+                },
+                "until" => {
+                    operations::flow::r#loop(&mut tokens, stack, variables, functions)?;
+                },
+                "for" => {
+                    operations::flow::r#for(&mut tokens, stack, variables, functions)?;
+                },
+                ":=" => {
+                    operations::flow::assign(stack, variables)?;
+                },
+                "set!" => {
+                    operations::flow::reassign(stack, variables)?;
+                },
+                "const" => {
+                    operations::flow::constant(stack, variables)?;
+                },
+                "fun" => {
+                    operations::flow::new_function(&mut tokens, stack, functions)?;
+                }
+                "->" => {
+                    operations::flow::bind(&mut tokens, stack, variables)?;
+                }
+                "toMap" => {
+                    operations::maps::to_map(stack)?;
+                }
+                "get" => {
+                    operations::maps::get(stack)?;
+                }
+                "put" => {
+                    operations::maps::put(stack)?;
+                }
+                "remove" => {
+                    operations::maps::remove(stack)?;
+                }
+                "hasKey" => {
+                    operations::maps::has_key(stack)?;
+                }
+                "keys" => {
+                    operations::maps::keys(stack)?;
+                }
+                "values" => {
+                    operations::maps::values(stack)?;
+                }
+                "assert" => {
+                    operations::contracts::assert(stack)?;
+                }
+                "assertEq" => {
+                    operations::contracts::assert_eq(stack)?;
+                }
+                "closure" => {
+                    operations::flow::closure(stack, variables)?;
+                }
+                "macro" => {
+                    operations::flow::new_macro(stack, functions)?;
+                }
+                "exec" => {
+                    operations::flow::exec(stack, variables, functions)?;
+                }
+                "try" => {
+                    operations::flow::try_block(&mut tokens, stack, variables, functions)?;
+                }
+                "throw" => {
+                    operations::flow::throw(stack)?;
+                }
+                "break" => {
+                    operations::flow::r#break()?;
+                }
+                "continue" => {
+                    operations::flow::r#continue()?;
+                }
+                "return" => {
+                    operations::flow::r#return()?;
+                }
+                _ => {
+                    stack.push(convert(token));
                 }
-                Ok(())
             }
-            _ => Err(format!("Invalid operation, expected block")),             // only block can be executed
+        }
+        Ok(())
+    }
+
+    /**
+     * This function turns a list or a sequence into a sequence, so both can be gone through the same way.
+     * Other values return None.
+     */
+    pub fn into_sequence(self) -> Option<Sequence> {
+        match self {
+            Value::List(list) => Some(Sequence::List(list, 0)),
+            Value::Seq(sequence) => Some(sequence),
+            _ => None,
         }
     }
 
     /**
-     * This function executes a block for each element in a list.
+     * This function executes a block for each element in a list or sequence.
      * It takes a stack, a block, a variables object, and a functions object as arguments.
-     * If the value is not a list or block, it will return an error.
+     * If the value is not a list, sequence or block, it will return an error.
      */
-    pub fn each(self, stack: &mut Stack, block: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
-        match (self, &block) {
-            (Value::List(list), Value::Block(_)) => {
-                for item in list {
-                    stack.push(item.clone());
-                    block.exec(stack, variables, functions)?;
+    pub fn each(self, stack: &mut Stack, block: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+        match (self.into_sequence(), &block) {
+            (Some(mut items), Value::Block(_) | Value::Closure(..)) => {
+                while let Some(item) = items.next(stack, variables, functions)? {
+                    stack.push(item);
+                    if !operations::flow::run_body(&block, stack, variables, functions)? {
+                        break;
+                    }
                 }
                 Ok(())
             },
-            _ => Err(format!("Invalid operation")),
+            _ => Err("Invalid operation".into()),
         }
     }
 
@@ -359,41 +953,49 @@ impl Value {
      * It takes a stack, a block, a variables object, and a functions object as arguments.
      * If the value is not a list or block, it will return an error.
      */
-    pub fn map(self, stack: &mut Stack, block: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
+    pub fn map(self, stack: &mut Stack, block: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
         match (self, &block) {
-            (Value::List(list), Value::Block(_)) => {
+            (Value::List(list), Value::Block(_) | Value::Closure(..)) => {
                 let mut result = Vec::new();
                 for item in list {
+                    let depth = stack.len();
                     stack.push(item.clone());
-                    block.exec(stack, variables, functions)?;
+                    if !operations::flow::run_body(&block, stack, variables, functions)? {
+                        stack.truncate(depth);
+                        break;      // the list only holds the elements mapped before the break
+                    }
                     result.push(stack.pop()?);
                 }
                 stack.push(Value::List(result));
                 Ok(())
             },
-            _ => Err(format!("Invalid operation")),
+            _ => Err("Invalid operation".into()),
         }
     }
 
     /**
-     * This function applies a block to each element in a list and returns a single value by folding the elements from left to right.
+     * This function applies a block to each element in a list or sequence and returns a single value by folding the elements from left to right.
      * It takes a stack, a block, a start value, a variables object, and a functions object as arguments.
-     * If the value is not a list or block, it will return an error.
+     * If the value is not a list, sequence or block, it will return an error.
      */
-    pub fn foldl(self, stack: &mut Stack, block: Self, start_value: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), String> {
-        match (self, &block) {
-            (Value::List(list), Value::Block(_)) => {
+    pub fn foldl(self, stack: &mut Stack, block: Self, start_value: Self, variables: &mut variables::Variables, functions: &mut functions::Functions) -> Result<(), Signal> {
+        match (self.into_sequence(), &block) {
+            (Some(mut items), Value::Block(_) | Value::Closure(..)) => {
                 let mut result = start_value;
-                for item in list {
+                while let Some(item) = items.next(stack, variables, functions)? {
+                    let depth = stack.len();
                     stack.push(result.clone());
                     stack.push(item.clone());
-                    block.exec(stack, variables, functions)?;
+                    if !operations::flow::run_body(&block, stack, variables, functions)? {
+                        stack.truncate(depth);
+                        break;      // the result is the one from before the break
+                    }
                     result = stack.pop()?;
                 }
                 stack.push(result);
                 Ok(())
             },
-            _ => Err(format!("Invalid operation")),
+            _ => Err("Invalid operation".into()),
         }
     }
 
@@ -443,23 +1045,26 @@ impl From<Vec<String>> for Value {
 }
 
 // Implement Into trait for String, i64, f64, bool, and Vec<Value> to convert them to Value
-impl Into<String> for Value {
-    fn into(self) -> String {
-        match self {
+impl From<Value> for String {
+    fn from(val: Value) -> Self {
+        match val {
             Value::String(value) => value,
             Value::Int(value) => value.to_string(),
             Value::Float(value) => value.to_string(),
             Value::Boolean(value) => value.to_string(),
             Value::List(value) => format!("[{}]", value.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" ")),
-            Value::Block(value) => format!("{:?}", value),
+            Value::Map(_) => val.to_string(),
+            Value::Block(value) | Value::Closure(value, _) => format!("{:?}", value),
+            Value::Seq(_) => "<sequence>".to_string(),
+            Value::Generator(_) => "<generator>".to_string(),
             Value::Symbol(value) => value,
         }
     }
 }
 
-impl Into<i64> for Value {
-    fn into(self) -> i64 {
-        match self {
+impl From<Value> for i64 {
+    fn from(val: Value) -> Self {
+        match val {
             Value::Int(value) => value,
             Value::Float(value) => value as i64,
             _ => panic!("Invalid conversion"),
@@ -467,9 +1072,9 @@ impl Into<i64> for Value {
     }
 }
 
-impl Into<bool> for Value {
-    fn into(self) -> bool {
-        match self {
+impl From<Value> for bool {
+    fn from(val: Value) -> Self {
+        match val {
             Value::Boolean(value) => value,
             Value::Int(value) => value != 0,
             Value::Float(value) => value != 0.0,
@@ -485,14 +1090,14 @@ impl Add for Value {
 
     fn add(self, other: Self) -> Self::Output {
         match (self, other) {    // pattern matching on self and other
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),    
+            (Value::Int(a), Value::Int(b)) => a.checked_add(b).map(Value::Int).ok_or("Integer overflow in +".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
             (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
             (Value::List(mut a), Value::List(b)) => {
                 a.extend(b);
                 Ok(Value::List(a))
             },
-            _ => Err(format!("Invalid operation + got values of different types")),
+            _ => Err("Invalid operation + got values of different types".to_string()),
         }
     }
 }
@@ -503,9 +1108,9 @@ impl Sub for Value {
 
     fn sub(self, other: Self) -> Self::Output {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+            (Value::Int(a), Value::Int(b)) => a.checked_sub(b).map(Value::Int).ok_or("Integer overflow in -".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
-            _ => Err(format!("Invalid operation - got values of different types")),
+            _ => Err("Invalid operation - got values of different types".to_string()),
         }
     }
 }
@@ -515,9 +1120,9 @@ impl Mul for Value {
     type Output = Result<Value, String>;
     fn mul(self, other: Self) -> Self::Output {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
+            (Value::Int(a), Value::Int(b)) => a.checked_mul(b).map(Value::Int).ok_or("Integer overflow in *".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
-            _ => Err(format!("Invalid operation *")),
+            _ => Err("Invalid operation *".to_string()),
         }
     }
 }
@@ -529,19 +1134,19 @@ impl Div for Value {
         match (self, other) {
             (Value::Int(a), Value::Int(b)) => {
                 if b == 0 {
-                    Err(format!("Division by zero"))
+                    Err("Division by zero".to_string())
                 } else {
-                    Ok(Value::Int(a / b))
+                    a.checked_div(b).map(Value::Int).ok_or("Integer overflow in /".to_string())
                 }
             },
             (Value::Float(a), Value::Float(b)) => {
                 if b == 0.0 || a.is_nan() {
-                    Err(format!("Division by zero or NaN"))
+                    Err("Division by zero or NaN".to_string())
                 } else {
                     Ok(Value::Float(a / b))
                 }
             },
-            _ => Err(format!("Invalid operation /, got values of different types")),
+            _ => Err("Invalid operation /, got values of different types".to_string()),
         }
     }
 }
@@ -555,18 +1160,24 @@ impl PartialEq for Value {
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Int(a), Value::Int(b)) => a == b,
             (Value::List(a), Value::List(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (Value::Symbol(a), Value::Symbol(b)) => a == b,
             _ => false,
         }
     }
 }
 
 // Implement PartialOrd trait for Value to compare two values
+// Integers and floats are compared by their value, so 1 and 1.0 are neither smaller nor bigger than each other.
+// Values of types that can not be compared, and NaN, give None. The order between types is only used by sort, see total_cmp.
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
             (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
             (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
-            (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (Value::String(a), Value::String(b)) | (Value::Symbol(a), Value::Symbol(b)) => a.partial_cmp(b),
             (Value::Boolean(a), Value::Boolean(b)) => a.partial_cmp(b),
             (Value::List(a), Value::List(b)) => a.partial_cmp(b),
             _ => None,
@@ -574,6 +1185,53 @@ impl PartialOrd for Value {
     }
 }
 
+impl Value {
+    /**
+     * This function returns the place of the type of a value in the order between types.
+     * Booleans come first, then numbers, strings, symbols, lists, maps, blocks, sequences and generators.
+     */
+    fn type_rank(&self) -> u8 {
+        match self {
+            Value::Boolean(_) => 0,
+            Value::Int(_) | Value::Float(_) => 1,
+            Value::String(_) => 2,
+            Value::Symbol(_) => 3,
+            Value::List(_) => 4,
+            Value::Map(_) => 5,
+            Value::Block(_) | Value::Closure(..) => 6,
+            Value::Seq(_) => 7,
+            Value::Generator(_) => 8,
+        }
+    }
+
+    /**
+     * This function compares two values in a total order, so any two values can be compared, it is used to sort.
+     * Values of different types are ordered by their type, and integers and floats are compared by their value,
+     * with the integer first if they are the same number, so the order is the same every time. Lists and maps are compared element by element,
+     * blocks by their code, and sequences and generators are all equal to each other.
+     */
+    pub fn total_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).total_cmp(b).then(Ordering::Less),
+            (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f64)).then(Ordering::Greater),
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) | (Value::Symbol(a), Value::Symbol(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => {
+                a.iter().zip(b).map(|(a, b)| a.total_cmp(b)).find(|order| order.is_ne()).unwrap_or(a.len().cmp(&b.len()))
+            },
+            (Value::Map(a), Value::Map(b)) => {
+                a.iter().zip(b).map(|((ka, va), (kb, vb))| ka.cmp(kb).then_with(|| va.total_cmp(vb)))
+                    .find(|order| order.is_ne()).unwrap_or(a.len().cmp(&b.len()))
+            },
+            (Value::Block(a) | Value::Closure(a, _), Value::Block(b) | Value::Closure(b, _)) => a.cmp(b),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+}
+
 // Implement Display trait for Value to print the value
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -586,9 +1244,15 @@ impl Display for Value {
                 // print the list as a string with spaces between each element
                 write!(f, "[ {} ]", a.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" "))
             },
-            Value::Block(a) => {
+            Value::Map(a) => {
+                // print the map with every name followed by a colon and its value
+                write!(f, "{{ {} }}", a.iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<String>>().join(" "))
+            },
+            Value::Block(a) | Value::Closure(a, _) => {
                 write!{f, "{:?}", a}    // print the block as a string
             },
+            Value::Seq(_) => write!(f, "<sequence>"),     // a sequence can not be printed without making its elements
+            Value::Generator(_) => write!(f, "<generator>"),
             Value::Symbol(a) => write!(f, "{}", a),
         }
     }
@@ -1,27 +1,121 @@
-use std::collections::HashMap;
+use std::{collections::{HashMap, HashSet}, sync::{Arc, Mutex}};
 
 use crate::types::Value;
 
+/**
+ * A Scope holds local bindings.
+ * It is shared, so a closure and all copies of it see the same bindings.
+ */
+pub type Scope = Arc<Mutex<HashMap<String, Value>>>;
+
+/**
+ * The Variables struct holds the global variables and a stack of local scopes.
+ * The top-level code has its own scope, and every function call gets a new one.
+ * Only the innermost scope is visible, so a function does not see the local bindings of its caller.
+ * Global variables can be constants, which can never be assigned again.
+ * In strict mode a global variable can only be changed with an explicit reassignment.
+ */
+#[derive(Clone)]
 pub struct Variables {
     pub variables: HashMap<String, Value>,
+    constants: HashSet<String>,
+    strict: bool,
+    scopes: Vec<Scope>,
 }
 
 impl Variables {
     pub fn new() -> Self {
         Variables {
             variables: HashMap::new(),
+            constants: HashSet::new(),
+            strict: false,
+            scopes: vec![Scope::default()],
         }
     }
 
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
     pub fn set(&mut self, name: &str, value: Value) {
         self.variables.insert(name.to_string(), value);
     }
 
-    pub fn get(&self, name: &str) -> Option<&Value> {
+    /**
+     * This function assigns a global variable, this is what := does.
+     * Constants can not be assigned, and in strict mode neither can variables that are already defined.
+     */
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), String> {
+        if self.constants.contains(name) {
+            return Err(format!("{} is a constant and can not be changed", name));
+        }
+        if self.strict && self.variables.contains_key(name) {
+            return Err(format!("{} is already defined, use set! to change it", name));
+        }
+        self.set(name, value);
+        Ok(())
+    }
+
+    /**
+     * This function changes a global variable that is already defined, this is what set! does.
+     */
+    pub fn reassign(&mut self, name: &str, value: Value) -> Result<(), String> {
+        if self.constants.contains(name) {
+            return Err(format!("{} is a constant and can not be changed", name));
+        }
+        if !self.variables.contains_key(name) {
+            return Err(format!("{} is not defined, use := to define it", name));
+        }
+        self.set(name, value);
+        Ok(())
+    }
+
+    /**
+     * This function defines a global constant, this is what const does.
+     * A name that is already defined can not be made a constant.
+     */
+    pub fn define_constant(&mut self, name: &str, value: Value) -> Result<(), String> {
         if self.variables.contains_key(name) {
-            Some(&self.variables[name])
-        } else {
-            None
+            return Err(format!("{} is already defined and can not be made a constant", name));
+        }
+        self.set(name, value);
+        self.constants.insert(name.to_string());
+        Ok(())
+    }
+
+    /**
+     * This function looks up a name, first in the innermost local scope and then in the global variables.
+     */
+    pub fn get(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.scopes.last().and_then(|scope| scope.lock().unwrap().get(name).cloned()) {
+            return Some(value);
+        }
+        self.variables.get(name).cloned()
+    }
+
+    /**
+     * This function binds a name in the innermost local scope.
+     */
+    pub fn bind(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last() {
+            scope.lock().unwrap().insert(name.to_string(), value);
         }
     }
-}
\ No newline at end of file
+
+    pub fn push_scope(&mut self, scope: Scope) {
+        self.scopes.push(scope);
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /**
+     * This function copies the bindings of the innermost local scope into a new scope.
+     * It is used by closures, so they keep the values the bindings had when the closure was made.
+     */
+    pub fn capture(&self) -> Scope {
+        let bindings = self.scopes.last().map(|scope| scope.lock().unwrap().clone()).unwrap_or_default();
+        Arc::new(Mutex::new(bindings))
+    }
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn closure_keeps_the_local_bindings_from_when_it_was_made() {
+    let program = "makeAdder { -> n { n + } closure } fun add5 5 makeAdder := [ 1 2 3 ] map { add5 exec }";
+    assert_eq!(run(program), Ok("[ 6 7 8 ]".to_string()));
+}
+
+#[test]
+fn bindings_made_in_a_closure_are_kept_between_runs() {
+    let program = "makeCounter { 0 -> count { count 1 + -> count count } closure } fun c makeCounter := c exec pop c exec pop c exec";
+    assert_eq!(run(program), Ok("3".to_string()));
+}
+
+#[test]
+fn local_names_hide_global_variables() {
+    assert_eq!(run("x 1 := f { x } fun 5 -> x f x +"), Ok("6".to_string()));
+}
+
+#[test]
+fn function_does_not_see_the_local_names_of_its_caller() {
+    assert_eq!(run("f { -> a g } fun g { a } fun 1 f"), Ok("a".to_string()));
+}
+
+#[test]
+fn closure_needs_a_block() {
+    assert_eq!(run_err("1 closure"), "Invalid operation, closure expects a block");
+}
@@ -0,0 +1,47 @@
+/*!
+ * This module runs programs with the bprog binary for the tests, the same way a program file is run.
+ * Not every test file uses every function.
+ */
+#![allow(dead_code)]
+
+use std::{env, fs, process::{self, Command}, sync::atomic::{AtomicUsize, Ordering}};
+
+// every program gets its own file, since the tests run at the same time
+static PROGRAMS: AtomicUsize = AtomicUsize::new(0);
+
+/**
+ * This function runs a program with the given options.
+ * It returns what the program printed, with the value left on the stack last, or the error the program stopped with.
+ */
+pub fn run_with(options: &[&str], program: &str) -> Result<String, String> {
+    let path = env::temp_dir().join(format!("bprog-test-{}-{}", process::id(), PROGRAMS.fetch_add(1, Ordering::SeqCst)));
+    fs::write(&path, program).expect("could not write the program");
+    let output = Command::new(env!("CARGO_BIN_EXE_bprog")).args(options).arg(&path).output().expect("could not run bprog");
+    fs::remove_file(&path).ok();
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string());
+    }
+    let errors = String::from_utf8_lossy(&output.stderr);
+    let mut lines = errors.lines();
+    match lines.by_ref().position(|line| line.contains("panicked at")) {
+        Some(_) => Err(lines.next().unwrap_or_default().to_string()),      // the message is on the line after the panic
+        None => Err(errors.trim().to_string()),
+    }
+}
+
+/**
+ * This function runs a program without options.
+ */
+pub fn run(program: &str) -> Result<String, String> {
+    run_with(&[], program)
+}
+
+/**
+ * This function runs a program that is expected to fail, and returns the error.
+ */
+pub fn run_err(program: &str) -> String {
+    match run(program) {
+        Ok(output) => panic!("expected {} to fail, but it printed {}", program, output),
+        Err(error) => error,
+    }
+}
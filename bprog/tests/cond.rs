@@ -0,0 +1,33 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn cond_runs_the_body_of_the_first_true_condition() {
+    let program = "sign { cond { { dup 0 < } { pop \" negative \" } { dup 0 == } { pop \" zero \" } { pop \" positive \" } } } fun \
+                   [ -5 0 5 ] map { sign }";
+    assert_eq!(run(program), Ok("[ \" negative \" \" zero \" \" positive \" ]".to_string()));
+}
+
+#[test]
+fn case_runs_the_body_of_the_equal_value() {
+    let program = "name { case { 1 \" one \" 2 \" two \" \" other \" } } fun [ 1 2 3 ] map { name }";
+    assert_eq!(run(program), Ok("[ \" one \" \" two \" \" other \" ]".to_string()));
+    assert_eq!(run("\" b \" case { \" a \" 1 \" b \" 2 }"), Ok("2".to_string()));
+}
+
+#[test]
+fn case_matches_quoted_symbols() {
+    assert_eq!(run("'red case { 'red { 1 } 'green { 2 } }"), Ok("1".to_string()));
+    assert_eq!(run("'green case { 'red { 1 } 'green { 2 } }"), Ok("2".to_string()));
+}
+
+#[test]
+fn without_a_default_nothing_is_run() {
+    assert_eq!(run("0 3 case { 1 { 1 + } }"), Ok("0".to_string()));
+}
+
+#[test]
+fn condition_has_to_push_a_boolean() {
+    assert_eq!(run_err("cond { { 1 } { 2 } }"), "Invalid syntax, a condition in cond has to push a boolean");
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::{run, run_err, run_with};
+
+#[test]
+fn constant_has_its_value() {
+    assert_eq!(run("x 1 const x 1 +"), Ok("2".to_string()));
+}
+
+#[test]
+fn constant_can_not_be_assigned_again() {
+    assert_eq!(run_err("x 1 const 'x 2 :="), "x is a constant and can not be changed");
+}
+
+#[test]
+fn defined_name_can_not_be_made_a_constant() {
+    assert_eq!(run_err("x 1 := 'x 2 const"), "x is already defined and can not be made a constant");
+}
+
+#[test]
+fn variable_can_be_assigned_again_without_strict() {
+    assert_eq!(run("x 1 := 'x 2 := x"), Ok("2".to_string()));
+}
+
+#[test]
+fn strict_needs_set_to_change_a_variable() {
+    assert_eq!(run_with(&["--strict"], "age 20 := 'age 21 := age"), Err("age is already defined, use set! to change it".to_string()));
+    assert_eq!(run_with(&["--strict"], "age 20 := 'age 21 set! age"), Ok("21".to_string()));
+}
+
+#[test]
+fn set_only_changes_defined_variables() {
+    assert_eq!(run_err("'age 21 set!"), "age is not defined, use := to define it");
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{run, run_err, run_with};
+
+const SQUARE: &str = "square [ Int ] { dup * } fun requires { dup -1 > } ensures { dup 100 < } ";
+
+#[test]
+fn assert_fails_on_false() {
+    assert_eq!(run("1 1 == assert 1"), Ok("1".to_string()));
+    assert_eq!(run_err("1 2 == assert 1"), "Assertion failed: expected true, got false");
+    assert_eq!(run("try { false assert } catch { }"), Ok("\" Assertion failed: expected true, got false \"".to_string()));
+}
+
+#[test]
+fn assert_eq_shows_both_values() {
+    assert_eq!(run_err("3 4 assertEq 1"), "Assertion failed: expected 4, got 3");
+}
+
+#[test]
+fn contract_passes_on_good_arguments() {
+    assert_eq!(run(&format!("{} 5 square", SQUARE)), Ok("25".to_string()));
+}
+
+#[test]
+fn failed_conditions_are_errors() {
+    assert_eq!(run_err(&format!("{} -2 square", SQUARE)), "Precondition of square failed on [-2]");
+    assert_eq!(run_err(&format!("{} 11 square", SQUARE)), "Postcondition of square failed on [121]");
+}
+
+#[test]
+fn contracts_can_be_turned_off() {
+    assert_eq!(run_with(&["--no-contracts"], &format!("{} -2 square", SQUARE)), Ok("4".to_string()));
+}
+
+#[test]
+fn contract_needs_a_signature() {
+    let error = run_err("square { dup * } fun requires { true }");
+    assert_eq!(error, "Invalid syntax, a function with a contract needs a signature like [ Int ], use [ Any ] for any value");
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn return_leaves_the_function() {
+    let program = "firstOver { -> limit each { dup limit > if { return } { pop } } -1 } fun [ 1 5 9 12 ] 6 firstOver";
+    assert_eq!(run(program), Ok("9".to_string()));
+}
+
+#[test]
+fn break_leaves_the_innermost_loop() {
+    assert_eq!(run("0 loop { false } { 1 + dup 5 == if { break } { } }"), Ok("5".to_string()));
+    assert_eq!(run("0 5 times { 1 + dup 3 == if { break } { } }"), Ok("3".to_string()));
+}
+
+#[test]
+fn continue_goes_on_with_the_next_iteration() {
+    assert_eq!(run("0 [ 1 2 3 4 ] each { dup 2 == if { pop continue } { } + }"), Ok("8".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 0 foldl { dup 2 == if { pop continue } { } + }"), Ok("4".to_string()));
+}
+
+#[test]
+fn break_in_map_keeps_the_results_before_it() {
+    assert_eq!(run("[ 1 2 3 4 ] map { dup 3 == if { break } { } 10 * }"), Ok("[ 10 20 ]".to_string()));
+}
+
+#[test]
+fn break_and_return_outside_of_their_place_are_errors() {
+    assert_eq!(run_err("break"), "break outside of a loop");
+    assert_eq!(run_err("f { break } fun [ 1 ] each { f }"), "break outside of a loop");
+    assert_eq!(run_err("1 return"), "return outside of a function");
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::{env, fs, process};
+
+use common::{run, run_err};
+
+/**
+ * This function writes CSV text to a file and runs the program on the text read from it,
+ * since a string literal can not hold a newline.
+ */
+fn run_on_file(name: &str, text: &str, program: &str) -> Result<String, String> {
+    let path = env::temp_dir().join(format!("bprog-csv-{}-{}.csv", process::id(), name));
+    fs::write(&path, text).unwrap();
+    let result = run(&format!("\" {} \" readFile {}", path.to_string_lossy(), program));
+    fs::remove_file(&path).ok();
+    result
+}
+
+#[test]
+fn numeric_columns_are_inferred() {
+    assert_eq!(run_on_file("numbers", "a,1,1.5\nb,2,3\n", "parseCsv"), Ok("[ [ \" a \" 1 1.5 ] [ \" b \" 2 3 ] ]".to_string()));
+}
+
+#[test]
+fn quoted_fields_can_hold_the_delimiter_and_quotes() {
+    assert_eq!(run_on_file("quoted", "\"x,y\",\"say \"\"hi\"\"\"\n", "parseCsv"), Ok("[ [ \" x,y \" \" say \"hi\" \" ] ]".to_string()));
+}
+
+#[test]
+fn header_makes_maps() {
+    let options = "[ [ \" header \" true ] [ \" delimiter \" \" ; \" ] ] toMap parseCsvWith";
+    assert_eq!(run_on_file("header", "name;age\nAnn;30\n", options), Ok("[ { age: 30 name: \" Ann \" } ]".to_string()));
+}
+
+#[test]
+fn columns_that_would_not_be_written_back_the_same_stay_strings() {
+    assert_eq!(run_on_file("zip", "007\n08\n", "parseCsv"), Ok("[ [ \" 007 \" ] [ \" 08 \" ] ]".to_string()));
+    assert_eq!(run("\" +1,0,-0.5 \" parseCsv"), Ok("[ [ \" +1 \" 0 -0.5 ] ]".to_string()));
+    assert_eq!(run("\" 1e999,9223372036854775808 \" parseCsv"), Ok("[ [ \" 1e999 \" \" 9223372036854775808 \" ] ]".to_string()));
+}
+
+#[test]
+fn write_rows_and_maps() {
+    assert_eq!(run("[ [ 1 \" a b \" ] [ 2 \" x,y \" ] ] toCsv"), Ok("\" 1,a b\n2,\"x,y\"\n \"".to_string()));
+    assert_eq!(run("[ [ a 1 ] ] toMap [ [ b 2 ] ] toMap [ ] cons cons toCsv"), Ok("\" a,b\n1,\n,2\n \"".to_string()));
+}
+
+#[test]
+fn invalid_csv_is_an_error() {
+    assert_eq!(run_err("\" \"a \" parseCsv"), "Invalid CSV on line 1, a quoted field is not closed");
+    assert_eq!(run_err("[ [ [ 1 ] ] ] toCsv"), "Invalid operation, a List can not be a CSV field");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, run_err};
+
+const SHOW: &str = "show [ Int ] { 10 * } fun 'show [ List ] { length } fun 'show { pop 0 } fun ";
+
+#[test]
+fn implementation_is_picked_by_type() {
+    assert_eq!(run(&format!("{} 4 show", SHOW)), Ok("40".to_string()));
+    assert_eq!(run(&format!("{} [ 1 2 3 ] show", SHOW)), Ok("3".to_string()));
+}
+
+#[test]
+fn untyped_implementation_is_the_fallback() {
+    assert_eq!(run(&format!("{} \" text \" show", SHOW)), Ok("0".to_string()));
+}
+
+#[test]
+fn most_concrete_implementation_wins() {
+    let program = "f [ Any Int ] { pop pop 1 } fun 'f [ Int Int ] { pop pop 2 } fun 'f [ Int ] { pop 3 } fun ";
+    assert_eq!(run(&format!("{} 1 2 f", program)), Ok("2".to_string()));
+    assert_eq!(run(&format!("{} 1.5 2 f", program)), Ok("1".to_string()));
+}
+
+#[test]
+fn no_matching_implementation_is_an_error() {
+    assert_eq!(run_err("f [ Int ] { } fun \" a \" f"), "No implementation of f matches the stack [\" a \"], available: [Int]");
+}
+
+#[test]
+fn equally_good_implementations_are_ambiguous() {
+    assert_eq!(run_err("f [ Any Int ] { } fun 'f [ Int Any ] { } fun 1 1 f"), "Ambiguous call to f, [Any Int] and [Int Any] match the stack [1 1]");
+}
+
+#[test]
+fn defining_the_same_types_again_replaces_it() {
+    assert_eq!(run("f [ Int ] { 1 + } fun 'f [ Int ] { 2 + } fun 1 f"), Ok("3".to_string()));
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn error_is_caught_as_its_message() {
+    assert_eq!(run("try { 10 0 / } catch { }"), Ok("\" Division by zero \"".to_string()));
+}
+
+#[test]
+fn thrown_value_is_caught_as_it_is() {
+    assert_eq!(run("try { 5 throw } catch { 1 + }"), Ok("6".to_string()));
+    assert_eq!(run_err("5 throw"), "Uncaught exception: 5");
+}
+
+#[test]
+fn stack_is_restored_before_catch() {
+    assert_eq!(run("1 2 try { pop pop 1 0 / } catch { pop } +"), Ok("3".to_string()));
+}
+
+#[test]
+fn finally_runs_after_success_and_after_an_error() {
+    assert_eq!(run("try { 1 } finally { 2 + }"), Ok("3".to_string()));
+    assert_eq!(run("x 0 := try { try { 1 0 / } finally { 'x 1 := } } catch { pop x }"), Ok("1".to_string()));
+}
+
+#[test]
+fn try_needs_catch_or_finally() {
+    assert_eq!(run_err("try { 1 }"), "Invalid syntax, try expects catch or finally after the block");
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn placeholders_take_values_in_order_by_position_or_by_name() {
+    assert_eq!(run("\" Ann \" 30 \" {} is {} years \" format"), Ok("\" Ann is 30 years \"".to_string()));
+    assert_eq!(run("1 2 \" {1}{0}{{}} \" format"), Ok("\" 21{} \"".to_string()));
+    assert_eq!(run("[ [ name \" Ann \" ] [ age 30 ] ] toMap \" {name} is {age} \" format"), Ok("\" Ann is 30 \"".to_string()));
+}
+
+#[test]
+fn specifiers_align_pad_and_round() {
+    assert_eq!(run("3.14159 \" {:>8.2} \" format"), Ok("\"     3.14 \"".to_string()));
+    assert_eq!(run("42 \" {:05} \" format"), Ok("\" 00042 \"".to_string()));
+    assert_eq!(run("\" x \" \" {:*^7} \" format"), Ok("\" ***x*** \"".to_string()));
+}
+
+#[test]
+fn interpolated_string_uses_variables() {
+    assert_eq!(run("name \" Ann \" := age 7 := f\" {name} is {age:>3} \""), Ok("\" Ann is   7 \"".to_string()));
+}
+
+#[test]
+fn invalid_templates_are_errors() {
+    assert_eq!(run_err("1 \" { \" format"), "Invalid template, a placeholder is not closed in {");
+    assert_eq!(run_err("1 \" {:.2} \" format"), "Invalid format specifier, a precision can not be used with Int");
+}
+
+#[test]
+fn width_can_not_overflow_or_be_huge() {
+    let error = run_err("1 \" {:99999999999999999999999} \" format");
+    assert_eq!(error, "Invalid format specifier 99999999999999999999999, a width or precision can be at most 10000");
+    assert_eq!(run("1 \" {:10000} \" format length"), Ok("10000".to_string()));
+    assert!(run_err("1.5 \" {:.10001} \" format").starts_with("Invalid format specifier .10001"));
+}
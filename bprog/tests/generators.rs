@@ -0,0 +1,47 @@
+mod common;
+
+use common::{run, run_err};
+
+const COUNTER: &str = "counter { 0 -> n loop { false } { n yield n 1 + -> n } } generator := ";
+
+#[test]
+fn next_runs_the_generator_to_the_next_yield() {
+    assert_eq!(run(&format!("{} counter next pop counter next pop counter next pop [ ] cons cons cons", COUNTER)), Ok("[ 0 1 2 ]".to_string()));
+}
+
+#[test]
+fn next_pushes_false_when_the_generator_has_ended() {
+    assert_eq!(run("g { 1 yield } generator := g next g next g next [ ] cons cons cons cons"), Ok("[ 1 true false false ]".to_string()));
+}
+
+#[test]
+fn any_value_can_be_yielded() {
+    assert_eq!(run("g { 'done yield } generator := g next [ ] cons cons"), Ok("[ done true ]".to_string()));
+}
+
+#[test]
+fn generator_can_be_used_as_a_sequence() {
+    assert_eq!(run(&format!("{} counter lazy 5 take collect", COUNTER)), Ok("[ 0 1 2 3 4 ]".to_string()));
+}
+
+#[test]
+fn error_in_the_generator_is_raised_by_next() {
+    assert_eq!(run_err("{ 1 0 / } generator next"), "Division by zero");
+    assert_eq!(run_err("1 yield"), "yield outside of a generator");
+}
+
+#[test]
+fn generators_that_are_dropped_do_not_count_towards_the_limit() {
+    assert_eq!(run("0 1 2000 1 for { pop { 1 yield } generator next pop + }"), Ok("2000".to_string()));
+}
+
+#[test]
+fn too_many_generators_is_an_error() {
+    let error = run_err("[ ] 1 1001 1 for { pop { 1 yield } generator swap cons }");
+    assert_eq!(error, "Invalid operation, there can be at most 1000 generators at the same time");
+}
+
+#[test]
+fn generator_has_its_own_depth_limit() {
+    assert_eq!(run_err("f { f } fun { f } generator next"), "maximum recursion depth exceeded (1000)");
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn parse_objects_arrays_and_null() {
+    let program = "\" {\"name\": \"bprog\", \"tags\": [1, 2.5, null]} \" parseJson \" tags \" get";
+    assert_eq!(run(program), Ok("[ 1 2.5 null ]".to_string()));
+}
+
+#[test]
+fn floats_are_written_so_they_are_read_back_as_floats() {
+    assert_eq!(run("[ 1 2.0 true ] toJson"), Ok("\" [1,2.0,true] \"".to_string()));
+    assert_eq!(run("\" [1,2.0] \" parseJson toJson parseJson"), Ok("[ 1 2 ]".to_string()));
+}
+
+#[test]
+fn pretty_json_is_indented() {
+    assert_eq!(run("\" [1,{\"a\":null}] \" parseJson toJsonPretty"), Ok("\" [\n  1,\n  {\n    \"a\": null\n  }\n] \"".to_string()));
+}
+
+#[test]
+fn errors_tell_the_position() {
+    assert_eq!(run_err("\" [1, 2 \" parseJson"), "Invalid JSON at position 5: expected , or ]");
+    assert_eq!(run_err("\" [1e400] \" parseJson"), "Invalid JSON at position 1: the number is too big");
+    assert_eq!(run_err("{ 1 } toJson"), "Invalid operation, toJson can not write a Block, it has no JSON form");
+}
+
+#[test]
+fn deep_nesting_is_an_error() {
+    let program = format!("\" {}{} \" parseJson", "[".repeat(2_000_000), "]".repeat(2_000_000));
+    assert_eq!(run_err(&program), "Invalid JSON at position 1000: too deeply nested");
+    let program = format!("\" {}{} \" parseJson toJson length", "[".repeat(1000), "]".repeat(1000));
+    assert_eq!(run(&program), Ok("2000".to_string()));
+    let error = run_err("[ ] 1 1000 1 for { pop [ ] cons } toJson");
+    assert_eq!(error, "Invalid operation, toJson can not write lists and maps nested more than 1000 deep");
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn folds_and_scans() {
+    assert_eq!(run("[ 1 2 3 ] 0 foldr { + }"), Ok("6".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 0 scan { + }"), Ok("[ 0 1 3 6 ]".to_string()));
+}
+
+#[test]
+fn searching_with_a_block() {
+    assert_eq!(run("[ 1 2 3 ] any { 2 > }"), Ok("true".to_string()));
+    assert_eq!(run("[ 1 2 3 ] all { 2 > }"), Ok("false".to_string()));
+    assert_eq!(run("[ 1 2 3 ] find { 5 > }"), Ok("none".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 2 indexOf"), Ok("1".to_string()));
+}
+
+#[test]
+fn grouping_and_partitioning() {
+    assert_eq!(run("[ 1 2 3 4 ] groupBy { 2 > }"), Ok("[ [ false [ 1 2 ] ] [ true [ 3 4 ] ] ]".to_string()));
+    assert_eq!(run("[ 1 2 3 4 ] partition { 2 > } [ ] cons cons"), Ok("[ [ 3 4 ] [ 1 2 ] ]".to_string()));
+}
+
+#[test]
+fn reshaping_lists() {
+    assert_eq!(run("[ 1 2 ] [ a b c ] zip dup unzip [ ] cons cons cons"), Ok("[ [ [ 1 a ] [ 2 b ] ] [ 1 2 ] [ a b ] ]".to_string()));
+    assert_eq!(run("[ [ 1 ] [ 2 3 ] ] flatten [ 1 1 2 ] unique [ ] cons cons"), Ok("[ [ 1 2 3 ] [ 1 2 ] ]".to_string()));
+    assert_eq!(run("[ 1 2 3 4 5 ] 2 chunk [ 1 2 3 ] 2 windows [ ] cons cons"), Ok("[ [ [ 1 2 ] [ 3 4 ] [ 5 ] ] [ [ 1 2 ] [ 2 3 ] ] ]".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 1 nth [ 1 2 3 ] 2 take [ 1 2 3 ] 2 drop [ ] cons cons cons"), Ok("[ 2 [ 1 2 ] [ 3 ] ]".to_string()));
+    assert_eq!(run_err("[ 1 2 3 ] 5 nth"), "Invalid index, 5 is out of range for a list of length 3");
+}
+
+#[test]
+fn with_variants_take_the_block_from_the_stack() {
+    assert_eq!(run("[ 1 2 3 ] { 10 * } mapWith"), Ok("[ 10 20 30 ]".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 0 { + } foldlWith"), Ok("6".to_string()));
+    assert_eq!(run_err("[ 1 2 3 ] mapWith"), "Invalid operation, expected a block on top of the stack, got [ 1 2 3 ]");
+}
+
+#[test]
+fn block_below_a_word_is_a_value() {
+    assert_eq!(run("[ 1 2 3 ] { 1 } foldl { pop } exec"), Ok("1".to_string()));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn while_runs_as_long_as_the_condition_is_true() {
+    assert_eq!(run("0 while { dup 5 < } { 1 + }"), Ok("5".to_string()));
+    assert_eq!(run("10 while { false } { 1 + }"), Ok("10".to_string()));
+}
+
+#[test]
+fn until_runs_until_the_condition_is_true() {
+    assert_eq!(run("0 until { dup 5 > } { 1 + }"), Ok("6".to_string()));
+}
+
+#[test]
+fn for_pushes_every_index() {
+    assert_eq!(run("0 1 10 1 for { + }"), Ok("55".to_string()));
+}
+
+#[test]
+fn for_can_bind_the_index_and_count_down() {
+    assert_eq!(run("[ ] 10 1 -3 for -> i { i swap cons }"), Ok("[ 1 4 7 10 ]".to_string()));
+}
+
+#[test]
+fn empty_range_does_not_run_the_block() {
+    assert_eq!(run("0 5 1 1 for { + }"), Ok("0".to_string()));
+}
+
+#[test]
+fn step_of_zero_is_an_error() {
+    assert_eq!(run_err("1 5 0 for { }"), "Invalid syntax, the step of for can not be zero");
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn macro_reads_a_block_from_the_code() {
+    let unless = "unless 1 { swap not if { exec } { pop } } macro ";
+    assert_eq!(run(&format!("{} 5 10 > unless {{ 7 }}", unless)), Ok("7".to_string()));
+    assert_eq!(run(&format!("{} 0 5 10 < unless {{ 7 + }}", unless)), Ok("0".to_string()));
+}
+
+#[test]
+fn macro_can_run_its_argument_more_than_once() {
+    assert_eq!(run("twice 1 { f swap := f exec f exec } macro 1 twice { 2 * }"), Ok("4".to_string()));
+}
+
+#[test]
+fn arguments_are_pushed_in_the_order_they_are_written() {
+    assert_eq!(run("pair 2 { [ ] cons cons } macro pair { 1 } 2"), Ok("[ [\"1\"] [\"2\"] ]".to_string()));
+}
+
+#[test]
+fn macro_without_enough_arguments_is_an_error() {
+    assert_eq!(run_err("m 1 { } macro m"), "Invalid syntax, expected a block or a word");
+}
+
+#[test]
+fn macro_needs_a_number_of_arguments() {
+    assert_eq!(run_err("m -1 { } macro 1"), "Invalid syntax, a macro needs the number of arguments it reads");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn modulo_remainder_power_and_roots() {
+    assert_eq!(run("-7 3 mod -7 3 rem 2 10 pow [ ] cons cons cons"), Ok("[ 2 -1 1024 ]".to_string()));
+    assert_eq!(run("16 sqrt 4.0 =="), Ok("true".to_string()));
+    assert_eq!(run("0 log"), Ok("-inf".to_string()));
+    assert_eq!(run_err("1 0 mod"), "Division by zero");
+    assert_eq!(run_err("2 -1 pow"), "Invalid operation, pow with integers expects an exponent that is zero or more");
+}
+
+#[test]
+fn rounding_and_extremes() {
+    assert_eq!(run("2.5 floor 2.5 ceil 2.5 round -3 abs [ ] cons cons cons cons"), Ok("[ 2 3 3 3 ]".to_string()));
+    assert_eq!(run("12 18 gcd 4 6 lcm 3 7 min 3 7.5 max [ ] cons cons cons cons"), Ok("[ 6 12 3 7.5 ]".to_string()));
+    assert_eq!(run_err("-1.0 sqrt floor"), "Invalid operation, floor of NaN does not fit in an integer");
+}
+
+#[test]
+fn bitwise_words() {
+    let program = "12 10 bitAnd 12 10 bitOr 12 10 bitXor 0 bitNot 1 4 shl -16 2 shr [ ] cons cons cons cons cons cons";
+    assert_eq!(run(program), Ok("[ 8 14 6 -1 16 -4 ]".to_string()));
+    assert_eq!(run_err("1 64 shl"), "Invalid operation, shl expects a shift between 0 and 63, got 64");
+}
+
+#[test]
+fn constants_do_not_take_common_names() {
+    assert_eq!(run("PI E +"), Ok("5.859874482048838".to_string()));
+    assert_eq!(run("e 3 := pi 2 := e pi +"), Ok("5".to_string()));
+}
+
+#[test]
+fn quoted_name_can_be_defined_over_a_math_word() {
+    assert_eq!(run("'max { pop } fun 1 2 max"), Ok("1".to_string()));
+    assert_eq!(run("'log 7 := log"), Ok("7".to_string()));
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn return_stack_puts_values_aside() {
+    assert_eq!(run("1 2 3 >r + r> [ ] cons cons"), Ok("[ 3 3 ]".to_string()));
+    assert_eq!(run("1 >r r@ r> +"), Ok("2".to_string()));
+    assert_eq!(run_err("r>"), "Return stack is empty");
+}
+
+#[test]
+fn function_has_to_leave_the_return_stack_balanced() {
+    assert_eq!(run_err("f { 1 >r } fun f"), "Return stack is not balanced at the end of f, 1 values were left on it");
+}
+
+#[test]
+fn try_restores_the_return_stack_before_catch() {
+    assert_eq!(run("f { try { 5 >r 1 0 / } catch { pop } 1 } fun f"), Ok("1".to_string()));
+    assert_eq!(run("1 >r try { r> 1 0 / } catch { pop r> }"), Ok("1".to_string()));
+}
+
+#[test]
+fn named_stacks_are_made_when_they_are_used() {
+    assert_eq!(run("1 'a >stack 2 'a >stack 'a stackDepth 'a stack> 'a stack@ [ ] cons cons cons"), Ok("[ 2 2 1 ]".to_string()));
+    assert_eq!(run("'b stackDepth"), Ok("0".to_string()));
+    assert_eq!(run_err("'a stack>"), "Stack a is empty");
+}
+
+#[test]
+fn try_restores_the_named_stacks_before_catch() {
+    assert_eq!(run("1 'a >stack try { 'a stack> 2 'a >stack 1 0 / } catch { pop 'a stack> 'a stackDepth + }"), Ok("1".to_string()));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn list_pattern_binds_nested_elements_and_the_rest() {
+    assert_eq!(run("[ 1 [ 2 3 ] 4 5 ] -> [ a [ b c ] rest... ] a b + c + rest length +"), Ok("8".to_string()));
+}
+
+#[test]
+fn wildcard_and_literals_match_without_binding() {
+    assert_eq!(run("[ 1 5 ] -> [ _ x ] x"), Ok("5".to_string()));
+    assert_eq!(run("[ 'on 5 ] -> [ 'on x ] x"), Ok("5".to_string()));
+}
+
+#[test]
+fn record_pattern_binds_the_keys_of_a_map() {
+    assert_eq!(run("[ [ name \" Ann \" ] [ age 30 ] ] toMap -> { name age } age"), Ok("30".to_string()));
+}
+
+#[test]
+fn mismatch_tells_what_was_expected() {
+    assert_eq!(run_err("[ 1 2 ] -> [ a b c ]"), "Pattern mismatch: expected a list of 3 elements, got [ 1 2 ]");
+    assert_eq!(run_err("[ [ a 1 ] ] toMap -> { b }"), "Pattern mismatch: the map has no key b");
+}
+
+#[test]
+fn maps_keep_their_keys_sorted() {
+    assert_eq!(run("[ [ b 1 ] [ a 2 ] ] toMap"), Ok("{ a: 2 b: 1 }".to_string()));
+    assert_eq!(run("[ [ a 1 ] ] toMap b 2 put c 3 put a remove values"), Ok("[ 2 3 ]".to_string()));
+    assert_eq!(run("[ [ a 1 ] ] toMap a hasKey"), Ok("true".to_string()));
+}
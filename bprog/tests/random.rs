@@ -0,0 +1,30 @@
+mod common;
+
+use common::{run, run_err, run_with};
+
+#[test]
+fn same_seed_gives_the_same_numbers() {
+    assert_eq!(run("42 seed 1 100 randomInt 42 seed 1 100 randomInt =="), Ok("true".to_string()));
+    let first = run_with(&["--seed", "7"], "[ 1 2 3 4 5 6 7 8 ] shuffle");
+    assert_eq!(run_with(&["--seed", "7"], "[ 1 2 3 4 5 6 7 8 ] shuffle"), first);
+}
+
+#[test]
+fn random_numbers_stay_in_their_range() {
+    assert_eq!(run("1 1 randomInt"), Ok("1".to_string()));
+    assert_eq!(run("randomFloat dup 0.0 >= swap 1.0 < &&"), Ok("true".to_string()));
+    assert_eq!(run_err("5 1 randomInt"), "Invalid operation, randomInt expects low to be at most high, got 5 1");
+}
+
+#[test]
+fn shuffle_and_sample_keep_the_elements() {
+    assert_eq!(run("[ 1 2 3 ] shuffle sort"), Ok("[ 1 2 3 ]".to_string()));
+    assert_eq!(run("[ 1 2 3 ] 3 sample sort"), Ok("[ 1 2 3 ]".to_string()));
+    assert_eq!(run("[ 7 ] choice"), Ok("7".to_string()));
+}
+
+#[test]
+fn impossible_picks_are_errors() {
+    assert_eq!(run_err("[ ] choice"), "Invalid operation, choice expects a list that is not empty");
+    assert_eq!(run_err("[ 1 2 ] 3 sample"), "Invalid operation, can not sample 3 elements from a list of length 2");
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{run, run_err, run_with};
+
+#[test]
+fn runaway_recursion_is_an_error() {
+    let error = run_err("f { f } fun f");
+    assert_eq!(error, "maximum recursion depth exceeded (10000)");
+}
+
+#[test]
+fn recursion_error_can_be_caught() {
+    assert_eq!(run("f { f } fun try { f } catch { pop 1 }"), Ok("1".to_string()));
+}
+
+#[test]
+fn recursion_below_the_limit_works() {
+    assert_eq!(run("down { dup 0 == if { } { 1 - down } } fun 3000 down"), Ok("0".to_string()));
+}
+
+#[test]
+fn max_depth_sets_the_limit() {
+    assert_eq!(run_with(&["--max-depth", "50"], "f { f } fun f"), Err("maximum recursion depth exceeded (50)".to_string()));
+    assert_eq!(run_with(&["--max-depth", "50000"], "down { dup 0 == if { } { 1 - down } } fun 20000 down"), Ok("0".to_string()));
+}
+
+#[test]
+fn max_depth_has_to_be_positive_and_at_most_the_limit() {
+    assert!(run_with(&["--max-depth", "0"], "1").unwrap_err().contains("--max-depth expects a positive integer"));
+    assert!(run_with(&["--max-depth", "100001"], "1").unwrap_err().contains("--max-depth can be at most 100000"));
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn match_and_find() {
+    assert_eq!(run("\" abc123 \" \" \\d+ \" reMatch"), Ok("true".to_string()));
+    assert_eq!(run("\" ab \" \" x \" reFind"), Ok("[  ]".to_string()));
+    assert_eq!(run("\" a1 b22 \" \" [a-z](\\d+) \" reFindAll"), Ok("[ [ \" a1 \" \" 1 \" ] [ \" b22 \" \" 22 \" ] ]".to_string()));
+}
+
+#[test]
+fn named_groups_are_captured_in_a_map() {
+    assert_eq!(run("\" x=1 \" \" (?P<k>\\w)=(?P<v>\\d) \" reCaptures"), Ok("{ k: \" x \" v: \" 1 \" }".to_string()));
+}
+
+#[test]
+fn replace_with_groups_or_a_block() {
+    assert_eq!(run("\" 2024-05-17 \" \" (\\d+)-(\\d+)-(\\d+) \" \" $3/$2/$1 \" reReplace"), Ok("\" 17/05/2024 \"".to_string()));
+    assert_eq!(run("\" a1 b22 \" \" \\d+ \" { head length toString } reReplace"), Ok("\" a1 b2 \"".to_string()));
+}
+
+#[test]
+fn split_on_matches() {
+    assert_eq!(run("\" a,b;c \" \" [,;] \" reSplit"), Ok("[ \" a \" \" b \" \" c \" ]".to_string()));
+}
+
+#[test]
+fn invalid_pattern_is_an_error() {
+    assert!(run_err("\" a \" \" ( \" reMatch").starts_with("Invalid regular expression ("));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn infinite_sequences_are_made_lazily() {
+    assert_eq!(run("1 from map { dup * } 5 take collect"), Ok("[ 1 4 9 16 25 ]".to_string()));
+    assert_eq!(run("1 iterate { 2 * } 5 take collect"), Ok("[ 1 2 4 8 16 ]".to_string()));
+    assert_eq!(run("0 repeat 3 take collect"), Ok("[ 0 0 0 ]".to_string()));
+}
+
+#[test]
+fn filter_and_drop_work_on_ranges() {
+    assert_eq!(run("1 10 range filter { 3 > } 2 drop collect"), Ok("[ 6 7 8 9 10 ]".to_string()));
+}
+
+#[test]
+fn each_on_an_infinite_sequence_stops_at_break() {
+    assert_eq!(run("0 1 from each { + dup 10 > if { break } { } }"), Ok("15".to_string()));
+}
+
+#[test]
+fn break_in_a_lazy_map_ends_the_sequence() {
+    assert_eq!(run("1 from map { break } 3 take collect"), Ok("[  ]".to_string()));
+    assert_eq!(run("1 from map { dup 3 > if { break } { 10 * } } 10 take collect"), Ok("[ 10 20 30 ]".to_string()));
+}
+
+#[test]
+fn continue_in_a_lazy_filter_leaves_the_element_out() {
+    let program = "1 from filter { dup 2 == if { continue } { } dup 6 > if { break } { } 3 > } 10 take collect";
+    assert_eq!(run(program), Ok("[ 4 5 6 ]".to_string()));
+}
+
+#[test]
+fn lazy_turns_a_list_into_a_sequence() {
+    assert_eq!(run("[ 1 2 3 ] lazy map { 1 + } collect"), Ok("[ 2 3 4 ]".to_string()));
+    assert!(run_err("1 lazy").starts_with("Invalid operation, lazy expects a list"));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn sort_in_both_directions() {
+    assert_eq!(run("[ 5 3 8 1 ] sort"), Ok("[ 1 3 5 8 ]".to_string()));
+    assert_eq!(run("[ 5 3 8 1 ] sortDesc"), Ok("[ 8 5 3 1 ]".to_string()));
+}
+
+#[test]
+fn comparator_can_push_a_boolean_or_an_integer() {
+    assert_eq!(run("[ 5 3 8 1 ] sortBy { > }"), Ok("[ 8 5 3 1 ]".to_string()));
+    assert_eq!(run("[ 5 3 8 1 ] sortBy { - }"), Ok("[ 1 3 5 8 ]".to_string()));
+    assert_eq!(run("[ 1 2 ] { > } sortByWith"), Ok("[ 2 1 ]".to_string()));
+}
+
+#[test]
+fn sort_on_a_key_is_stable() {
+    assert_eq!(run("[ \" bb \" \" a \" \" ccc \" ] sortOn { length }"), Ok("[ \" a \" \" bb \" \" ccc \" ]".to_string()));
+    assert_eq!(run("[ [ 1 b ] [ 0 a ] [ 1 a ] ] sortOn { head }"), Ok("[ [ 0 a ] [ 1 b ] [ 1 a ] ]".to_string()));
+}
+
+#[test]
+fn values_of_different_types_can_be_sorted_together() {
+    assert_eq!(run("[ 2 \" a \" true 1.5 ] sort"), Ok("[ true 1.5 2 \" a \" ]".to_string()));
+}
+
+#[test]
+fn comparisons_do_not_use_the_order_between_types() {
+    assert_eq!(run("1 1.0 <"), Ok("false".to_string()));
+    assert_eq!(run("1 2.5 <"), Ok("true".to_string()));
+    assert_eq!(run_err("1 \" a \" <"), "Invalid operation <, can not compare 1 and \" a \"");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn shuffling_words_move_the_top_elements() {
+    assert_eq!(run("1 2 3 rot [ ] cons cons cons"), Ok("[ 2 3 1 ]".to_string()));
+    assert_eq!(run("1 2 3 -rot [ ] cons cons cons"), Ok("[ 3 1 2 ]".to_string()));
+    assert_eq!(run("1 2 nip 3 tuck [ ] cons cons cons"), Ok("[ 3 2 3 ]".to_string()));
+    assert_eq!(run("1 2 2dup [ ] cons cons cons cons"), Ok("[ 1 2 1 2 ]".to_string()));
+    assert_eq!(run("1 2 3 2drop"), Ok("1".to_string()));
+}
+
+#[test]
+fn pick_and_roll_use_an_index_from_the_top() {
+    assert_eq!(run("1 2 3 2 pick 3 roll [ ] cons cons cons cons"), Ok("[ 2 3 1 1 ]".to_string()));
+}
+
+#[test]
+fn depth_and_clear_work_on_the_whole_stack() {
+    assert_eq!(run("1 2 3 depth [ ] cons cons cons cons"), Ok("[ 1 2 3 3 ]".to_string()));
+    assert_eq!(run("1 2 clear 5"), Ok("5".to_string()));
+}
+
+#[test]
+fn pick_past_the_bottom_is_an_error() {
+    assert_eq!(run_err("1 2 3 5 pick"), "Stack has too few values, 7 are needed but 4 are on the stack");
+    assert_eq!(run_err("1 -1 pick"), "Invalid operation, pick expects an index that is zero or more");
+}
+
+#[test]
+fn combinators_run_blocks_on_the_values_below() {
+    assert_eq!(run("5 { 1 + } { 2 * } bi [ ] cons cons"), Ok("[ 6 10 ]".to_string()));
+    assert_eq!(run("1 2 { 10 * } dip [ ] cons cons"), Ok("[ 10 2 ]".to_string()));
+    assert_eq!(run("3 { 1 + } keep [ ] cons cons"), Ok("[ 4 3 ]".to_string()));
+    assert_eq!(run("3 { 1 + } { 2 * } { 3 - } tri [ ] cons cons cons"), Ok("[ 4 6 0 ]".to_string()));
+    assert_eq!(run("3 [ { 1 + } { 2 * } { 3 - } ] cleave [ ] cons cons cons"), Ok("[ 4 6 0 ]".to_string()));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn split_and_join() {
+    assert_eq!(run("\" a,b,c \" \" , \" split"), Ok("[ \" a \" \" b \" \" c \" ]".to_string()));
+    assert_eq!(run("\" abc \" \" \" split"), Ok("[ \" a \" \" b \" \" c \" ]".to_string()));
+    assert_eq!(run("[ \" a \" \" b \" ] \" - \" join"), Ok("\" a-b \"".to_string()));
+}
+
+#[test]
+fn slice_counts_characters() {
+    assert_eq!(run("\" héllo \" 1 3 slice"), Ok("\" él \"".to_string()));
+    assert_eq!(run_err("\" héllo \" 1 9 slice"), "Invalid index, 9 is out of range for a string of length 5");
+}
+
+#[test]
+fn searching_in_strings() {
+    assert_eq!(run("\" hello \" \" ell \" contains"), Ok("true".to_string()));
+    assert_eq!(run("\" hello \" \" ll \" indexOf"), Ok("2".to_string()));
+    assert_eq!(run("\" hello \" \" x \" indexOf"), Ok("-1".to_string()));
+    assert_eq!(run("\" hello \" \" he \" startsWith"), Ok("true".to_string()));
+}
+
+#[test]
+fn changing_strings() {
+    assert_eq!(run("\" hello \" \" l \" \" L \" replace"), Ok("\" heLLo \"".to_string()));
+    assert_eq!(run("\" Hello \" dup upper swap lower +"), Ok("\" HELLOhello \"".to_string()));
+    assert_eq!(run("\" ab \" 3 repeatString"), Ok("\" ababab \"".to_string()));
+    assert_eq!(run("\" abc \" reverse"), Ok("\" cba \"".to_string()));
+}
+
+#[test]
+fn padding() {
+    assert_eq!(run("\" 7 \" 3 \" 0 \" padLeft"), Ok("\" 007 \"".to_string()));
+    assert_eq!(run("\" 7 \" 3 \" * \" padRight"), Ok("\" 7** \"".to_string()));
+}
+
+#[test]
+fn string_words_check_their_arguments() {
+    assert_eq!(run_err("\" hello \" 1 contains"), "Invalid operation, contains expects a string, got 1");
+}
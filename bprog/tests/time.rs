@@ -0,0 +1,33 @@
+mod common;
+
+use common::{run, run_err};
+
+#[test]
+fn format_a_timestamp() {
+    assert_eq!(run("1700000000 120 \" %Y-%m-%dT%H:%M:%S%z \" formatTimeAt"), Ok("\" 2023-11-15T00:13:20+02:00 \"".to_string()));
+    assert_eq!(run("0 \" %a %b %j \" formatTime"), Ok("\" Thu Jan 001 \"".to_string()));
+}
+
+#[test]
+fn parse_a_time_with_an_offset() {
+    assert_eq!(run("\" 2023-11-15T00:13:20+02:00 \" \" %Y-%m-%dT%H:%M:%S%z \" parseTime"), Ok("1700000000".to_string()));
+}
+
+#[test]
+fn day_of_the_year_can_be_past_the_end_of_january() {
+    assert_eq!(run("\" 2024-366 \" \" %Y-%j \" parseTime \" %Y-%m-%d \" formatTime"), Ok("\" 2024-12-31 \"".to_string()));
+    assert_eq!(run_err("\" 2023-366 \" \" %Y-%j \" parseTime"), "Invalid time \" 2023-366 \" for the format \" %Y-%j \"");
+}
+
+#[test]
+fn day_past_the_end_of_the_month_is_an_error() {
+    assert_eq!(run_err("\" 2024-02-45 \" \" %Y-%m-%d \" parseTime"), "Invalid time \" 2024-02-45 \" for the format \" %Y-%m-%d \"");
+    assert_eq!(run_err("\" 2023-02-29 \" \" %Y-%m-%d \" parseTime"), "Invalid time \" 2023-02-29 \" for the format \" %Y-%m-%d \"");
+    assert_eq!(run("\" 2024-02-29 \" \" %Y-%m-%d \" parseTime"), Ok("1709164800".to_string()));
+}
+
+#[test]
+fn clock_goes_forward_while_sleeping() {
+    assert_eq!(run("clock 10 sleep clock swap - 10.0 >="), Ok("true".to_string()));
+    assert_eq!(run("now 1700000000 >"), Ok("true".to_string()));
+}